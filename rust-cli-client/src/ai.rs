@@ -0,0 +1,236 @@
+//! Computer players, to play or test without a human opponent. They play on the column major
+//! gameboard of `TicTacToe`, with the rules of `game_engine`.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::game_engine::{Board, PlaceOutcome, MARK_EMPTY};
+use crate::game_loop::MoveInput;
+use crate::row_col::{Col, Row, RowCol};
+
+/// Chance of a blunder of `BotLevel::Blundering`, in percent, when not given
+pub const DEFAULT_BLUNDER_PERCENT: u8 = 30;
+/// Score of a win, minus the number of moves it takes
+const WIN_SCORE: i32 = 10;
+const CENTER: u8 = 4;
+const CORNERS: [u8; 4] = [0, 2, 6, 8];
+
+/// How well a bot plays, e.g. `depth:2`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BotLevel {
+    /// Any empty cell
+    Random,
+    /// Wins if it can, otherwise blocks the win of the opponent, otherwise takes the center or a
+    /// corner
+    Greedy,
+    /// Minimax looking that many moves ahead, its own included
+    DepthLimited(u8),
+    /// Perfect, except for playing its worst move with a chance of that many percent
+    Blundering(u8),
+    /// Minimax with alpha-beta pruning, which never loses
+    Perfect,
+}
+
+impl FromStr for BotLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, arg) = match s.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (s, None),
+        };
+        let parse_arg = |arg: &str| {
+            arg.parse::<u8>()
+                .map_err(|_| anyhow!("Invalid bot level argument: {}", arg))
+        };
+        Ok(match (name, arg) {
+            ("random", None) => BotLevel::Random,
+            ("greedy", None) => BotLevel::Greedy,
+            ("depth", Some(arg)) => match parse_arg(arg)? {
+                0 => bail!("Bots look at least 1 move ahead"),
+                plies => BotLevel::DepthLimited(plies),
+            },
+            ("blunder", None) => BotLevel::Blundering(DEFAULT_BLUNDER_PERCENT),
+            ("blunder", Some(arg)) => match parse_arg(arg)? {
+                percent if percent > 100 => bail!("Blunder chance {}% is over 100%", percent),
+                percent => BotLevel::Blundering(percent),
+            },
+            ("perfect", None) => BotLevel::Perfect,
+            _ => bail!(
+                "Invalid bot level {}, expected random, greedy, depth:<PLIES>, blunder[:<PERCENT>] \
+                 or perfect",
+                s
+            ),
+        })
+    }
+}
+
+impl Display for BotLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BotLevel::Random => write!(f, "random"),
+            BotLevel::Greedy => write!(f, "greedy"),
+            BotLevel::DepthLimited(plies) => write!(f, "depth:{}", plies),
+            BotLevel::Blundering(percent) => write!(f, "blunder:{}", percent),
+            BotLevel::Perfect => write!(f, "perfect"),
+        }
+    }
+}
+
+pub struct Bot {
+    level: BotLevel,
+    rng: StdRng,
+}
+
+impl Bot {
+    pub fn new(level: BotLevel) -> Self {
+        Self {
+            level,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Plays the same moves for the same seed, e.g. in tests
+    pub fn with_seed(level: BotLevel, seed: u64) -> Self {
+        Self {
+            level,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn level(&self) -> BotLevel {
+        self.level
+    }
+
+    /// Column major index of the cell to mark for the player of the current turn, `None` if the
+    /// game is over
+    pub fn choose(&mut self, board: &Board) -> Option<u8> {
+        let cells = board.empty_cells();
+        if board.finished != 0 || cells.is_empty() {
+            return None;
+        }
+        match self.level {
+            BotLevel::Random => cells.choose(&mut self.rng).copied(),
+            BotLevel::Greedy => Some(greedy(board, &cells)),
+            BotLevel::DepthLimited(plies) => self.best(board, Some(plies)),
+            BotLevel::Blundering(percent) if self.rng.gen_range(0..100) < percent => {
+                scores(board, None)
+                    .into_iter()
+                    .min_by_key(|&(_, score)| score)
+                    .map(|(cell, _)| cell)
+            }
+            BotLevel::Blundering(_) | BotLevel::Perfect => self.best(board, None),
+        }
+    }
+
+    /// One of the cells with the best score, at random so that games vary
+    fn best(&mut self, board: &Board, max_depth: Option<u8>) -> Option<u8> {
+        let scores = scores(board, max_depth);
+        let best = scores.iter().map(|&(_, score)| score).max()?;
+        let best_cells: Vec<u8> = scores
+            .iter()
+            .filter(|&&(_, score)| score == best)
+            .map(|&(cell, _)| cell)
+            .collect();
+        best_cells.choose(&mut self.rng).copied()
+    }
+}
+
+impl MoveInput for Bot {
+    fn next_move(&mut self, gameboard: &[u8]) -> Result<(Row, Col)> {
+        let cell = self
+            .choose(&board_of(gameboard))
+            .ok_or_else(|| anyhow!("Bot ({}) has no move, the game is finished", self.level))?;
+        let (row, col) = (cell % 3, cell / 3);
+        Ok((
+            RowCol::try_from(row).expect("Cells are within the 3x3 gameboard"),
+            RowCol::try_from(col).expect("Cells are within the 3x3 gameboard"),
+        ))
+    }
+}
+
+/// Board of an unfinished game from its gameboard alone, as every placed mark took a turn
+pub fn board_of(gameboard: &[u8]) -> Board {
+    Board {
+        gameboard: gameboard.to_vec(),
+        cur_turn: gameboard.iter().filter(|&&mark| mark != MARK_EMPTY).count() as u8,
+        finished: 0,
+    }
+}
+
+/// Scores of marking each empty cell, for the player of the current turn
+pub fn scores(board: &Board, max_depth: Option<u8>) -> Vec<(u8, i32)> {
+    board
+        .empty_cells()
+        .into_iter()
+        .map(|cell| {
+            let score = score_move(board, cell, 1, max_depth, -i32::MAX, i32::MAX);
+            (cell, score)
+        })
+        .collect()
+}
+
+/// Score of marking `cell`, positive if the player wins, the sooner the higher, and 0 for a draw or
+/// when it cannot tell within `max_depth` moves
+fn score_move(
+    board: &Board,
+    cell: u8,
+    depth: u8,
+    max_depth: Option<u8>,
+    alpha: i32,
+    beta: i32,
+) -> i32 {
+    let mut next = board.clone();
+    match next
+        .place_mark(cell)
+        .expect("Empty cells of unfinished games can be marked")
+    {
+        PlaceOutcome::Won(_) => WIN_SCORE - depth as i32,
+        PlaceOutcome::NextTurn(_) if max_depth.is_none_or(|max| depth < max) => {
+            -negamax(&next, depth + 1, max_depth, -beta, -alpha)
+        }
+        _ => 0,
+    }
+}
+
+/// Best score for the player of the current turn, skipping the moves which cannot change it
+fn negamax(board: &Board, depth: u8, max_depth: Option<u8>, mut alpha: i32, beta: i32) -> i32 {
+    let mut best = -i32::MAX;
+    for cell in board.empty_cells() {
+        let score = score_move(board, cell, depth, max_depth, alpha, beta);
+        best = best.max(score);
+        alpha = alpha.max(score);
+        if alpha >= beta {
+            break;
+        }
+    }
+    best
+}
+
+/// The win, otherwise the block of the opponent's win, otherwise the center or a corner
+fn greedy(board: &Board, cells: &[u8]) -> u8 {
+    let wins = |board: &Board, cell: u8| {
+        matches!(board.clone().place_mark(cell), Ok(PlaceOutcome::Won(_)))
+    };
+    // The same board with the opponent to play
+    let opponent = Board {
+        cur_turn: board.cur_turn + 1,
+        ..board.clone()
+    };
+    cells
+        .iter()
+        .copied()
+        .find(|&cell| wins(board, cell))
+        .or_else(|| cells.iter().copied().find(|&cell| wins(&opponent, cell)))
+        .or_else(|| {
+            std::iter::once(CENTER)
+                .chain(CORNERS)
+                .find(|cell| cells.contains(cell))
+        })
+        .unwrap_or(cells[0])
+}
@@ -1,12 +1,6 @@
-use sui_types::base_types::ObjectID;
-
-pub const SUI_FULLNODE_URL: &str = "https://rpc.testnet.sui.io:443";
-pub const PACKAGE_ID: ObjectID = ObjectID::new([
-    0x24, 0x6d, 0x9e, 0x49, 0x69, 0xa9, 0xfc, 0xe6, 0x84, 0xb9, 0x25, 0xd6, 0xcd, 0x01, 0xaa, 0xb7,
-    0x6c, 0xdb, 0x04, 0xb8, 0xdb, 0x1c, 0x3f, 0xda, 0xec, 0x36, 0x66, 0x44, 0x35, 0x68, 0x04, 0x57,
-]);
 pub const MODULE_NAME: &str = "multisig_tic_tac_toe";
 pub const TIC_TAC_TOE_STRUCT_NAME: &str = "TicTacToe";
 pub const MARK_STRUCT_NAME: &str = "Mark";
+pub const TROPHY_STRUCT_NAME: &str = "TicTacToeTrophy";
 
 pub const TX_GAS_BUDGET: u64 = 10_000_000;
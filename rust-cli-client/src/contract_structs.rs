@@ -1,24 +1,65 @@
-use std::collections::BTreeMap;
+use move_core_types::language_storage::StructTag;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 
-use anyhow::{Result, anyhow, bail};
-use serde::{Serialize, Deserialize};
-
-use sui_sdk::rpc_types::SuiMoveValue;
 use sui_types::base_types::{ObjectID, SuiAddress};
-use sui_types::id::{UID, ID};
+use sui_types::gas_coin::GasCoin;
+use sui_types::id::{ID, UID};
+use sui_types::Identifier;
+
+use crate::consts::{MARK_STRUCT_NAME, MODULE_NAME, TIC_TAC_TOE_STRUCT_NAME, TROPHY_STRUCT_NAME};
+use crate::error::TicTacToeError;
+
+/// Structs which are decoded from the BCS contents of the objects of their Move type
+pub trait MoveStruct: DeserializeOwned {
+    const STRUCT_NAME: &'static str;
+
+    /// Move type of the struct, the contract being published at `package_id`
+    fn struct_tag(package_id: ObjectID) -> StructTag {
+        StructTag {
+            address: package_id.into(),
+            module: Identifier::new(MODULE_NAME).expect("Module name is a valid identifier"),
+            name: Identifier::new(Self::STRUCT_NAME).expect("Struct names are valid identifiers"),
+            type_params: vec![],
+        }
+    }
+}
 
-type SuiMoveStructFields = BTreeMap<String, SuiMoveValue>;
+/// Decodes the BCS contents of an object, after checking that the object is of the Move type of
+/// `T`
+pub fn decode_move_struct<T: MoveStruct>(
+    package_id: ObjectID,
+    type_: &StructTag,
+    bcs_bytes: &[u8],
+) -> Result<T, TicTacToeError> {
+    let expected = T::struct_tag(package_id);
+    if *type_ != expected {
+        return Err(TicTacToeError::UnexpectedType {
+            expected: Box::new(expected),
+            found: Box::new(type_.clone()),
+        });
+    }
+    bcs::from_bytes(bcs_bytes).map_err(|e| TicTacToeError::InvalidLayout {
+        struct_name: T::STRUCT_NAME,
+        reason: e.to_string(),
+    })
+}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TicTacToeTrophy {
     pub id: ObjectID,
     pub winner: SuiAddress,
     pub loser: SuiAddress,
+    /// 1: X, 2: O
     pub played_as: u8,
     pub game_id: ObjectID,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl MoveStruct for TicTacToeTrophy {
+    const STRUCT_NAME: &'static str = TROPHY_STRUCT_NAME;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TicTacToe {
     pub id: UID,
     /// Column major 3x3 game board
@@ -30,64 +71,11 @@ pub struct TicTacToe {
     pub finished: u8, // 0 not finished, 1 X Winner, 2 O Winner, 3 Draw
 }
 
-impl TryFrom<&SuiMoveStructFields> for TicTacToe {
-    type Error = anyhow::Error;
-
-    fn try_from(fields: &SuiMoveStructFields) -> Result<Self> {
-
-        let SuiMoveValue::UID { id } = *fields
-            .get("id")
-            .ok_or(anyhow!("Missing field id"))? else {
-                bail!("Field id is not UID");
-            };
-        let SuiMoveValue::Number(cur_turn) = fields
-            .get("cur_turn")
-            .ok_or(anyhow!("Missing field cur_turn"))? else {
-                bail!("Field cur_turn is not Number");
-            };
-        let cur_turn = *cur_turn as u8;
-        let SuiMoveValue::Number(finished) = fields
-            .get("finished")
-            .ok_or(anyhow!("Missing field finished"))? else {
-                bail!("Field finished is not Number");
-            };
-        let finished = *finished as u8;
-        let SuiMoveValue::Vector(gameboard) = fields
-            .get("gameboard")
-            .ok_or(anyhow!("Missing field gameboard"))? else {
-                bail!("Field gameboard is not Vector");
-            };
-        let gameboard = gameboard
-            .iter()
-            .map(|v| {
-                let SuiMoveValue::Number(n) = v else { panic!("Field gameboard is not Vector of Numbers") };
-                *n as u8
-            })
-            .collect::<Vec<u8>>();
-        let SuiMoveValue::Address(x_addr) = *fields
-            .get("x_addr")
-            .ok_or(anyhow!("Missing field x_addr"))? else {
-                bail!("Field x_addr is not Address");
-            };
-        let SuiMoveValue::Address(o_addr) = *fields
-            .get("o_addr")
-            .ok_or(anyhow!("Missing field o_addr"))? else {
-                bail!("Field o_addr is not Address");
-            };
-
-        Ok(Self {
-            id: UID { id: ID { bytes: id } },
-            cur_turn,
-            finished,
-            gameboard,
-            x_addr,
-            o_addr,
-        })
-    }
+impl MoveStruct for TicTacToe {
+    const STRUCT_NAME: &'static str = TIC_TAC_TOE_STRUCT_NAME;
 }
 
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Mark {
     pub id: UID,
     /// Column major 3x3 placement
@@ -100,3 +88,15 @@ pub struct Mark {
     pub game_id: ID,
 }
 
+impl MoveStruct for Mark {
+    const STRUCT_NAME: &'static str = MARK_STRUCT_NAME;
+}
+
+/// Gas coins are not part of the contract, so their type does not depend on the package
+impl MoveStruct for GasCoin {
+    const STRUCT_NAME: &'static str = "Coin";
+
+    fn struct_tag(_package_id: ObjectID) -> StructTag {
+        GasCoin::type_()
+    }
+}
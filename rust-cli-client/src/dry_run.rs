@@ -0,0 +1,82 @@
+//! Dry runs of the transactions before they are submitted, to pay them with a budget estimated from
+//! their cost instead of the fixed `TX_GAS_BUDGET`, and to catch their aborts without paying for
+//! them.
+
+use std::fmt::Display;
+
+use sui_types::gas::GasCostSummary;
+use sui_types::gas_coin::MIST_PER_SUI;
+
+/// Margin added to the estimated cost of a transaction for its budget
+pub const DEFAULT_GAS_MARGIN_PERCENT: u64 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DryRunMode {
+    /// Transactions are submitted with `TX_GAS_BUDGET`
+    #[default]
+    Off,
+    /// Transactions are dry run first and submitted with the estimated budget
+    Estimate { margin_percent: u64 },
+    /// Transactions are dry run and never submitted
+    Only,
+}
+
+impl DryRunMode {
+    pub fn is_enabled(&self) -> bool {
+        *self != DryRunMode::Off
+    }
+
+    /// Margin added to the estimated cost for the budget
+    pub fn margin_percent(&self) -> u64 {
+        match self {
+            DryRunMode::Estimate { margin_percent } => *margin_percent,
+            _ => DEFAULT_GAS_MARGIN_PERCENT,
+        }
+    }
+}
+
+/// Gas cost of a transaction, as reported by its dry run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasEstimate {
+    pub computation_cost: u64,
+    pub storage_cost: u64,
+    pub storage_rebate: u64,
+    /// Budget to submit the transaction with
+    pub budget: u64,
+}
+
+impl GasEstimate {
+    /// The budget covers the computation and storage costs plus the margin, as the rebate is only
+    /// paid back after the transaction. It is never below the gas price, which is the lowest
+    /// budget accepted.
+    pub fn new(summary: &GasCostSummary, gas_price: u64, margin_percent: u64) -> Self {
+        let cost = summary.computation_cost + summary.storage_cost;
+        let budget = cost.saturating_add(cost.saturating_mul(margin_percent) / 100);
+        GasEstimate {
+            computation_cost: summary.computation_cost,
+            storage_cost: summary.storage_cost,
+            storage_rebate: summary.storage_rebate,
+            budget: budget.max(gas_price),
+        }
+    }
+
+    /// What the transaction is expected to cost in the end, negative if the rebate is bigger
+    pub fn net_cost(&self) -> i64 {
+        (self.computation_cost + self.storage_cost) as i64 - self.storage_rebate as i64
+    }
+}
+
+impl Display for GasEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Expected gas cost: {} MIST ({} SUI), computation {}, storage {}, rebate {}, budget {}",
+            self.net_cost(),
+            self.net_cost() as f64 / MIST_PER_SUI as f64,
+            self.computation_cost,
+            self.storage_cost,
+            self.storage_rebate,
+            self.budget
+        )
+    }
+}
@@ -0,0 +1,277 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use move_core_types::language_storage::StructTag;
+use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::error::SuiObjectResponseError;
+use thiserror::Error;
+
+use crate::move_abort::MoveAbort;
+use crate::startup::Player;
+
+/// Errors of `TicTacToeClient` and the game backends, so that callers can branch on them instead
+/// of on messages.
+#[derive(Debug, Error)]
+pub enum TicTacToeError {
+    #[error("No available games")]
+    NoAvailableGames,
+    #[error("No game found with id {0}")]
+    GameNotFound(ObjectID),
+    #[error("No mark found")]
+    MarkNotFound,
+    #[error("No trophy found with id {0}")]
+    TrophyNotFound(ObjectID),
+    #[error("No executed transaction found with digest {0}")]
+    TransactionNotFound(TransactionDigest),
+    /// The node returned an error instead of one of the objects
+    #[error("Cannot read object: {0}")]
+    ObjectResponse(SuiObjectResponseError),
+    #[error("Owner of {0} is not an address")]
+    NotAddressOwned(ObjectID),
+    #[error("Cannot find gas coin for signer address [{address}] with amount sufficient for the required gas amount [{budget}].")]
+    NoGasCoin { address: SuiAddress, budget: u64 },
+    #[error("Gas budget {budget} is less than the reference gas price {gas_price}")]
+    GasBudgetTooLow { budget: u64, gas_price: u64 },
+    #[error("Expected an object of type {expected}, found {found}")]
+    UnexpectedType {
+        expected: Box<StructTag>,
+        found: Box<StructTag>,
+    },
+    /// The object on chain does not decode to the struct of the contract
+    #[error("Invalid layout of {struct_name}: {reason}")]
+    InvalidLayout {
+        struct_name: &'static str,
+        reason: String,
+    },
+    #[error(
+        "{} ({code}). {}",
+        .code.explanation(.function.as_deref()),
+        .code.suggestion(.function.as_deref())
+    )]
+    MoveAbort {
+        code: AbortCode,
+        /// Function of the contract which aborted
+        function: Option<String>,
+    },
+    #[error("Transaction failed: {0}")]
+    ExecutionFailed(String),
+    /// The move is outside of the 3x3 gameboard, found before submitting anything
+    #[error("Row {row} and column {col} should be in 0-2")]
+    InvalidMove { row: u8, col: u8 },
+    #[error("It is not the turn of {player} in game {game_id}")]
+    NotYourTurn { player: Player, game_id: ObjectID },
+    /// The mark of the player was sent to the game with another move, which can still be placed
+    #[error(
+        "Another move, at row {} and column {}, is waiting to be placed in game {game_id}",
+        .placement % 3,
+        .placement / 3
+    )]
+    PendingMove {
+        game_id: ObjectID,
+        mark_id: ObjectID,
+        /// Column major index of the cell of the move
+        placement: u8,
+    },
+    /// The validators rejected the transaction, as objects it uses are locked by a conflicting one
+    #[error("Objects are locked by a conflicting transaction: {reason}")]
+    ObjectsLocked {
+        objects: Vec<ObjectID>,
+        reason: String,
+    },
+    /// The validators rejected the transaction, as it was built from outdated object references
+    #[error("Objects have changed since the transaction was built: {0}")]
+    StaleObjects(String),
+    #[error("Insufficient gas, get more SUI to keep playing: {0}")]
+    InsufficientGas(String),
+    /// The game objects are locked by conflicting transactions, so the game cannot be played until
+    /// the end of the epoch
+    #[error(
+        "Game is locked until epoch end, by conflicting transactions{}{}",
+        objects_on(.objects),
+        epoch_end_in(.epoch_end)
+    )]
+    GameLocked {
+        objects: Vec<ObjectID>,
+        /// Current epoch and the time until it ends, if it could be fetched
+        epoch_end: Option<(u64, Duration)>,
+    },
+    #[error(
+        "Gas coins are locked until epoch end by conflicting transactions, and no other coin could \
+         be used{}",
+        objects_on(.0)
+    )]
+    GasLocked(Vec<ObjectID>),
+    #[error("Giving up after {attempts} attempts: {source}")]
+    RetriesExhausted {
+        attempts: u32,
+        source: Box<TicTacToeError>,
+    },
+    #[error("Cannot build transaction: {0}")]
+    TransactionBuild(anyhow::Error),
+    #[error("Cannot sign transaction: {0}")]
+    Signing(anyhow::Error),
+    #[error("RPC failed: {0}")]
+    Rpc(sui_sdk::error::Error),
+}
+
+/// Substrings of the rejections of conflicting transactions
+const LOCKED_PATTERNS: [&str; 4] = [
+    "ObjectLockConflict",
+    "ObjectsDoubleUsed",
+    "equivocated",
+    "ObjectLockedAtEpoch",
+];
+/// Substrings of the rejections of transactions built from outdated object references
+const STALE_PATTERNS: [&str; 2] = [
+    "ObjectVersionUnavailableForConsumption",
+    "not available for consumption",
+];
+/// Substrings of the rejections of transactions without enough gas
+const GAS_PATTERNS: [&str; 4] = [
+    "InsufficientGas",
+    "GasBalanceTooLow",
+    "InsufficientCoinBalance",
+    "GasBudgetTooLow",
+];
+
+impl From<sui_sdk::error::Error> for TicTacToeError {
+    /// The validators reject transactions with RPC errors, which only their messages tell apart
+    fn from(error: sui_sdk::error::Error) -> Self {
+        Self::from_rejection(&error.to_string()).unwrap_or(TicTacToeError::Rpc(error))
+    }
+}
+
+impl TicTacToeError {
+    /// Error of a transaction which failed during execution, decoding the aborts of the contract
+    /// published as `package_id`
+    pub fn from_execution_failure(error: &str, package_id: ObjectID) -> Self {
+        match MoveAbort::parse(error) {
+            Some(abort) => match abort.contract_code(package_id) {
+                Some(code) => TicTacToeError::MoveAbort {
+                    code,
+                    function: abort.function,
+                },
+                None => TicTacToeError::ExecutionFailed(error.to_string()),
+            },
+            None if error.contains("InsufficientGas") => {
+                TicTacToeError::InsufficientGas(error.to_string())
+            }
+            None => TicTacToeError::ExecutionFailed(error.to_string()),
+        }
+    }
+
+    /// Error of a transaction rejected by the validators, out of the message of the node. `None`
+    /// if it is not a rejection which can be recovered from.
+    pub fn from_rejection(msg: &str) -> Option<Self> {
+        if LOCKED_PATTERNS.iter().any(|p| msg.contains(p)) {
+            Some(TicTacToeError::ObjectsLocked {
+                objects: object_ids_in(msg),
+                reason: msg.to_string(),
+            })
+        } else if STALE_PATTERNS.iter().any(|p| msg.contains(p)) {
+            Some(TicTacToeError::StaleObjects(msg.to_string()))
+        } else if GAS_PATTERNS.iter().any(|p| msg.contains(p)) {
+            Some(TicTacToeError::InsufficientGas(msg.to_string()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Object ids in an error message, written as `0x` and 64 hex digits
+fn object_ids_in(msg: &str) -> Vec<ObjectID> {
+    let mut ids = vec![];
+    for (i, _) in msg.match_indices("0x") {
+        let hex: String = msg[i + 2..]
+            .chars()
+            .take_while(|c| c.is_ascii_hexdigit())
+            .collect();
+        if hex.len() != 64 {
+            continue;
+        }
+        if let Ok(id) = ObjectID::from_hex_literal(&format!("0x{}", hex)) {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
+
+fn objects_on(objects: &[ObjectID]) -> String {
+    if objects.is_empty() {
+        return String::new();
+    }
+    let objects: Vec<String> = objects.iter().map(ToString::to_string).collect();
+    format!(" on {}", objects.join(", "))
+}
+
+fn epoch_end_in(epoch_end: &Option<(u64, Duration)>) -> String {
+    match epoch_end {
+        Some((epoch, ends_in)) => format!(
+            ". Epoch {} ends in about {} minutes",
+            epoch,
+            ends_in.as_secs() / 60
+        ),
+        None => String::new(),
+    }
+}
+
+/// Abort codes of the contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbortCode {
+    EInvalidSize,
+    ETriedToCheat,
+    EMarkIsFromDifferentGame,
+}
+
+impl AbortCode {
+    /// Why the contract aborted, given the function which aborted
+    pub fn explanation(&self, function: Option<&str>) -> &'static str {
+        match (self, function) {
+            (AbortCode::EInvalidSize, _) => "The cell is outside of the 3x3 gameboard",
+            (AbortCode::ETriedToCheat, Some("delete_game")) => "The game is not finished yet",
+            (AbortCode::ETriedToCheat, _) => "The mark has already been sent to the game",
+            (AbortCode::EMarkIsFromDifferentGame, _) => "The mark belongs to another game",
+        }
+    }
+
+    /// What the player can do about it
+    pub fn suggestion(&self, function: Option<&str>) -> &'static str {
+        match (self, function) {
+            (AbortCode::EInvalidSize, _) => "Pick a row and a column between 0 and 2",
+            (AbortCode::ETriedToCheat, Some("delete_game")) => "Finish the game before deleting it",
+            (AbortCode::ETriedToCheat, _) => {
+                "Wait for the mark to be placed, it comes back on your next turn"
+            }
+            (AbortCode::EMarkIsFromDifferentGame, _) => {
+                "Play the game the mark was created for, see `show <GAME_ID>`"
+            }
+        }
+    }
+}
+
+impl TryFrom<u64> for AbortCode {
+    type Error = u64;
+
+    fn try_from(code: u64) -> Result<Self, Self::Error> {
+        match code {
+            0 => Ok(AbortCode::EInvalidSize),
+            1 => Ok(AbortCode::ETriedToCheat),
+            2 => Ok(AbortCode::EMarkIsFromDifferentGame),
+            _ => Err(code),
+        }
+    }
+}
+
+impl From<AbortCode> for u64 {
+    fn from(code: AbortCode) -> Self {
+        code as u64
+    }
+}
+
+impl Display for AbortCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} ({})", self, u64::from(*self))
+    }
+}
@@ -0,0 +1,498 @@
+use std::fmt::Display;
+use std::future::Future;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::future;
+use tokio::sync::watch;
+
+use sui_sdk::rpc_types::{
+    SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+};
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest};
+use sui_types::crypto::SuiKeyPair;
+use sui_types::object::Owner;
+use sui_types::storage::WriteKind;
+
+use crate::consts::TX_GAS_BUDGET;
+use crate::contract_structs::{Mark, TicTacToe, TicTacToeTrophy};
+use crate::error::TicTacToeError;
+use crate::game_engine::{self, Board, GameStatus, PlaceOutcome};
+use crate::multi_sig_1_out_of_n::MultiSig1OutOfN;
+use crate::recovery::execute_once_with_recovery;
+use crate::row_col::{Col, Row};
+use crate::startup::Player;
+use crate::tic_tac_toe_client::{FetchGameFilter, Result, TicTacToeClient, TrophyPage};
+
+/// Objects created by `create_game`
+#[derive(Debug, Clone)]
+pub struct GameInfo {
+    pub game_id: ObjectID,
+    pub mark_id: ObjectID,
+}
+
+/// Result of a whole move, once the mark has been placed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveOutcome {
+    /// The mark was placed and it is now the turn of the opponent
+    Placed,
+    /// The cell was already taken, so the contract passed the mark back to play again
+    CellOccupied,
+    Won(Player),
+    Draw,
+}
+
+impl MoveOutcome {
+    /// Outcome of a move placed during turn `turn`, out of the game after it
+    fn of_game(game: &TicTacToe, turn: u8) -> Result<Self> {
+        let status =
+            GameStatus::try_from(game.finished).map_err(|e| TicTacToeError::InvalidLayout {
+                struct_name: "TicTacToe",
+                reason: e.to_string(),
+            })?;
+        Ok(match status {
+            GameStatus::Won(player) => MoveOutcome::Won(player),
+            GameStatus::Draw => MoveOutcome::Draw,
+            GameStatus::InProgress if game.cur_turn > turn => MoveOutcome::Placed,
+            GameStatus::InProgress => MoveOutcome::CellOccupied,
+        })
+    }
+}
+
+impl From<PlaceOutcome> for MoveOutcome {
+    fn from(outcome: PlaceOutcome) -> Self {
+        match outcome {
+            PlaceOutcome::Rejected => MoveOutcome::CellOccupied,
+            PlaceOutcome::NextTurn(_) => MoveOutcome::Placed,
+            PlaceOutcome::Won(player) => MoveOutcome::Won(player),
+            PlaceOutcome::Draw => MoveOutcome::Draw,
+        }
+    }
+}
+
+impl Display for MoveOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveOutcome::Placed => write!(f, "Mark placed"),
+            MoveOutcome::CellOccupied => write!(f, "Cell is already taken, play again"),
+            MoveOutcome::Won(player) => write!(f, "{} won!", player),
+            MoveOutcome::Draw => write!(f, "Draw"),
+        }
+    }
+}
+
+/// The game operations of the contract, so that games can run either on a Sui network or locally.
+#[async_trait]
+pub trait GameBackend {
+    async fn create_game(
+        &self,
+        signer: &SuiKeyPair,
+        multisig: &MultiSig1OutOfN,
+    ) -> Result<GameInfo>;
+
+    async fn send_mark_to_game(
+        &self,
+        signer: &SuiKeyPair,
+        mark_id: ObjectID,
+        row: Row,
+        col: Col,
+    ) -> Result<TransactionDigest>;
+
+    /// Places the mark of the signer, which they sent to the game. Returns what the contract did
+    /// with it, out of the effects of the transaction.
+    async fn place_mark(
+        &self,
+        signer: &SuiKeyPair,
+        multisig: &MultiSig1OutOfN,
+        game_id: ObjectID,
+        mark_id: ObjectID,
+    ) -> Result<PlaceOutcome>;
+
+    /// Note that game should be finished
+    async fn delete_game(
+        &self,
+        signer: &SuiKeyPair,
+        multisig: &MultiSig1OutOfN,
+        game_id: ObjectID,
+    ) -> Result<TransactionDigest>;
+
+    async fn fetch_available_game(
+        &self,
+        multisig: &MultiSig1OutOfN,
+        fetch_filter: Option<&FetchGameFilter>,
+    ) -> Result<TicTacToe>;
+
+    async fn fetch_game(&self, game_id: ObjectID) -> Result<TicTacToe>;
+
+    async fn find_mark(&self, game_id: ObjectID, owner_addr: SuiAddress) -> Result<Mark>;
+
+    async fn mark_owner(&self, mark_id: ObjectID) -> Result<SuiAddress>;
+
+    async fn game_version(&self, game_id: ObjectID) -> Result<SequenceNumber>;
+
+    async fn fetch_trophy(&self, trophy_id: ObjectID) -> Result<TicTacToeTrophy>;
+
+    /// A page of at most `limit` trophies owned by `owner_addr`, starting after `cursor`
+    async fn fetch_trophies(
+        &self,
+        owner_addr: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> Result<TrophyPage>;
+
+    /// Makes the move of the signer at (row, col) on `board`, the game as they saw it: sends their
+    /// mark to the game and places it. The steps which already landed are skipped, so that it can
+    /// be called again after a failure. If the mark was sent with another move, that move is
+    /// returned as `TicTacToeError::PendingMove` for the caller to place.
+    async fn make_move(
+        &self,
+        signer: &SuiKeyPair,
+        multisig: &MultiSig1OutOfN,
+        game_id: ObjectID,
+        board: &Board,
+        row: Row,
+        col: Col,
+    ) -> Result<MoveOutcome> {
+        let player = multisig
+            .role_of(&signer.public())
+            .ok_or(TicTacToeError::Signing(anyhow!(
+                "Signer is not part of the multisig"
+            )))?;
+        let placement = game_engine::get_index(row.into(), col.into()).map_err(|_| {
+            TicTacToeError::InvalidMove {
+                row: row.into(),
+                col: col.into(),
+            }
+        })?;
+        let turn = board.cur_turn;
+        if game_engine::player_of_turn(turn) != player {
+            return Err(TicTacToeError::NotYourTurn { player, game_id });
+        }
+        let game = self.fetch_game(game_id).await?;
+
+        if game.finished != 0 || game.cur_turn != turn {
+            // The move landed already if it ended the turn, with the mark of the player in the cell
+            // which was empty
+            let landed_turn = if game.finished != 0 { turn } else { turn + 1 };
+            if game.cur_turn == landed_turn
+                && board.gameboard.get(placement as usize) == Some(&game_engine::MARK_EMPTY)
+                && game.gameboard.get(placement as usize) == Some(&game_engine::mark_of(player))
+            {
+                return MoveOutcome::of_game(&game, turn);
+            }
+            if game.finished != 0 {
+                return Err(TicTacToeError::ExecutionFailed(format!(
+                    "Game {} is already finished",
+                    game_id
+                )));
+            }
+            return Err(TicTacToeError::ExecutionFailed(format!(
+                "Game {} has moved past turn {}",
+                game_id, turn
+            )));
+        }
+
+        let signer_addr = SuiAddress::from(&signer.public());
+        let mark_id = match self.find_mark(game_id, signer_addr).await {
+            Ok(mark) => {
+                let mark_id = mark.id.id.bytes;
+                self.send_mark_to_game(signer, mark_id, row, col).await?;
+                mark_id
+            }
+            // The mark has been sent already, for this move only
+            Err(TicTacToeError::MarkNotFound) => {
+                let mark = self.find_mark(game_id, multisig.admin_address()).await?;
+                if let Some(sent) = mark.placement.filter(|&sent| sent != placement) {
+                    return Err(TicTacToeError::PendingMove {
+                        game_id,
+                        mark_id: mark.id.id.bytes,
+                        placement: sent,
+                    });
+                }
+                mark.id.id.bytes
+            }
+            Err(e) => return Err(e),
+        };
+        let outcome = self.place_mark(signer, multisig, game_id, mark_id).await?;
+        Ok(outcome.into())
+    }
+
+    /// Notifies of the changes which may concern the game of `game_info`. Without a subscription,
+    /// or once it is closed, the game is polled for changes instead.
+    fn subscribe_changes(&self, game_info: &GameInfo) -> Option<watch::Receiver<u64>>;
+}
+
+#[async_trait]
+impl GameBackend for TicTacToeClient {
+    async fn create_game(
+        &self,
+        signer: &SuiKeyPair,
+        multisig: &MultiSig1OutOfN,
+    ) -> Result<GameInfo> {
+        // Games there before, so that a game created by an attempt which seemed to fail is found
+        // instead of creating another one
+        let games_before: Vec<ObjectID> = TicTacToeClient::fetch_games(self, multisig, None)
+            .await?
+            .iter()
+            .map(|game| game.id.id.bytes)
+            .collect();
+        let games_before = &games_before;
+        self.recover_once(
+            signer,
+            &[],
+            || self.game_created_since(multisig, games_before),
+            |gas_ref| async move {
+                let resp = TicTacToeClient::create_game(self, signer, multisig, gas_ref).await?;
+                let effects = successful_effects(resp)?;
+
+                // The game is the only object created under the multisig-account and the mark the
+                // only one created under player X.
+                let game_id = created_object_owned_by(&effects, multisig.admin_address()).ok_or(
+                    TicTacToeError::ExecutionFailed(
+                        "No object created under multisig account".to_string(),
+                    ),
+                )?;
+                let mark_id = created_object_owned_by(&effects, multisig.x_address()).ok_or(
+                    TicTacToeError::ExecutionFailed(
+                        "No object created under first participant account".to_string(),
+                    ),
+                )?;
+                Ok(GameInfo { game_id, mark_id })
+            },
+        )
+        .await
+    }
+
+    async fn send_mark_to_game(
+        &self,
+        signer: &SuiKeyPair,
+        mark_id: ObjectID,
+        row: Row,
+        col: Col,
+    ) -> Result<TransactionDigest> {
+        self.recover(signer, &[mark_id], |gas_ref| async move {
+            let resp = TicTacToeClient::send_mark_to_game(self, signer, gas_ref, mark_id, row, col)
+                .await?;
+            successful_digest(resp)
+        })
+        .await
+    }
+
+    async fn place_mark(
+        &self,
+        signer: &SuiKeyPair,
+        multisig: &MultiSig1OutOfN,
+        game_id: ObjectID,
+        mark_id: ObjectID,
+    ) -> Result<PlaceOutcome> {
+        let signer_addr = SuiAddress::from(&signer.public());
+        self.recover(signer, &[mark_id, game_id], |gas_ref| async move {
+            let resp =
+                TicTacToeClient::place_mark(self, signer, multisig, gas_ref, game_id, mark_id)
+                    .await?;
+            let effects = successful_effects(resp)?;
+            placement_of(&effects, signer_addr, multisig, mark_id)
+        })
+        .await
+    }
+
+    async fn delete_game(
+        &self,
+        signer: &SuiKeyPair,
+        multisig: &MultiSig1OutOfN,
+        game_id: ObjectID,
+    ) -> Result<TransactionDigest> {
+        self.recover(signer, &[game_id], |gas_ref| async move {
+            let resp =
+                TicTacToeClient::delete_game(self, signer, multisig, gas_ref, game_id).await?;
+            successful_digest(resp)
+        })
+        .await
+    }
+
+    async fn fetch_available_game(
+        &self,
+        multisig: &MultiSig1OutOfN,
+        fetch_filter: Option<&FetchGameFilter>,
+    ) -> Result<TicTacToe> {
+        TicTacToeClient::fetch_available_game(self, multisig, fetch_filter).await
+    }
+
+    async fn fetch_game(&self, game_id: ObjectID) -> Result<TicTacToe> {
+        TicTacToeClient::fetch_game(self, game_id).await
+    }
+
+    async fn find_mark(&self, game_id: ObjectID, owner_addr: SuiAddress) -> Result<Mark> {
+        TicTacToeClient::find_mark(self, game_id, owner_addr).await
+    }
+
+    async fn mark_owner(&self, mark_id: ObjectID) -> Result<SuiAddress> {
+        TicTacToeClient::mark_owner(self, mark_id).await
+    }
+
+    async fn game_version(&self, game_id: ObjectID) -> Result<SequenceNumber> {
+        TicTacToeClient::game_version(self, game_id).await
+    }
+
+    async fn fetch_trophy(&self, trophy_id: ObjectID) -> Result<TicTacToeTrophy> {
+        TicTacToeClient::fetch_trophy(self, trophy_id).await
+    }
+
+    async fn fetch_trophies(
+        &self,
+        owner_addr: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> Result<TrophyPage> {
+        TicTacToeClient::fetch_trophies(self, owner_addr, cursor, limit).await
+    }
+
+    fn subscribe_changes(&self, game_info: &GameInfo) -> Option<watch::Receiver<u64>> {
+        Some(TicTacToeClient::subscribe_changes(
+            self,
+            vec![game_info.game_id, game_info.mark_id],
+        ))
+    }
+}
+
+impl TicTacToeClient {
+    /// Executes a transaction paid by the signer, recovering from the failures it can. Every
+    /// attempt is given a fresh reference to a gas coin of the signer which is not locked, reserved
+    /// for the attempt. The first of the game objects identifies the game for its dedicated coin.
+    async fn recover<T, F, Fut>(
+        &self,
+        signer: &SuiKeyPair,
+        game_objects: &[ObjectID],
+        attempt: F,
+    ) -> Result<T>
+    where
+        F: Fn(Option<ObjectRef>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.recover_once(signer, game_objects, || future::ready(Ok(None)), attempt)
+            .await
+    }
+
+    /// Same as `recover`, for transactions which should not land twice: before every retry,
+    /// `landed` looks for the result of a failed attempt which landed anyway.
+    async fn recover_once<T, L, LFut, F, Fut>(
+        &self,
+        signer: &SuiKeyPair,
+        game_objects: &[ObjectID],
+        landed: L,
+        attempt: F,
+    ) -> Result<T>
+    where
+        L: FnMut() -> LFut,
+        LFut: Future<Output = Result<Option<T>>>,
+        F: Fn(Option<ObjectRef>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let attempt = &attempt;
+        let res =
+            execute_once_with_recovery(self.retry_policy(), game_objects, landed, |locked_gas| {
+                let gas = self.reserve_gas(
+                    signer,
+                    game_objects.first().copied(),
+                    TX_GAS_BUDGET,
+                    locked_gas,
+                );
+                async move {
+                    let gas = gas.await?;
+                    attempt(Some(gas.object_ref())).await
+                }
+            })
+            .await;
+        match res {
+            Err(TicTacToeError::GameLocked { objects, .. }) => Err(TicTacToeError::GameLocked {
+                objects,
+                // It can be played again once the epoch ends
+                epoch_end: self.epoch_end().await.ok(),
+            }),
+            res => res,
+        }
+    }
+
+    /// A game of the multisig account which is not one of `games_before`, with its mark
+    async fn game_created_since(
+        &self,
+        multisig: &MultiSig1OutOfN,
+        games_before: &[ObjectID],
+    ) -> Result<Option<GameInfo>> {
+        let games = TicTacToeClient::fetch_games(self, multisig, None).await?;
+        let Some(game) = games
+            .iter()
+            .find(|game| !games_before.contains(&game.id.id.bytes))
+        else {
+            return Ok(None);
+        };
+        let game_id = game.id.id.bytes;
+        let mark = TicTacToeClient::find_mark(self, game_id, multisig.x_address()).await?;
+        Ok(Some(GameInfo {
+            game_id,
+            mark_id: mark.id.id.bytes,
+        }))
+    }
+}
+
+/// Returns the digest of a transaction, if it was executed successfully
+fn successful_digest(resp: SuiTransactionBlockResponse) -> Result<TransactionDigest> {
+    let digest = resp.digest;
+    successful_effects(resp)?;
+    Ok(digest)
+}
+
+/// Returns the effects of a transaction, which the client only returns if it was executed
+/// successfully
+fn successful_effects(resp: SuiTransactionBlockResponse) -> Result<SuiTransactionBlockEffects> {
+    resp.effects
+        .ok_or(TicTacToeError::ExecutionFailed("No effects".to_string()))
+}
+
+/// What `place_mark` did with the mark of the signer, out of the effects of its transaction
+fn placement_of(
+    effects: &SuiTransactionBlockEffects,
+    signer_addr: SuiAddress,
+    multisig: &MultiSig1OutOfN,
+    mark_id: ObjectID,
+) -> Result<PlaceOutcome> {
+    // The only object the contract creates is the trophy of the winner
+    for player in [Player::X, Player::O] {
+        if created_object_owned_by(effects, multisig.player_address(player)).is_some() {
+            return Ok(PlaceOutcome::Won(player));
+        }
+    }
+    if effects.deleted().iter().any(|obj| obj.object_id == mark_id) {
+        return Ok(PlaceOutcome::Draw);
+    }
+    let mark = effects
+        .mutated()
+        .iter()
+        .find(|obj| obj.object_id() == mark_id)
+        .ok_or(TicTacToeError::ExecutionFailed(format!(
+            "Mark {} is not in the effects of the transaction",
+            mark_id
+        )))?;
+    match &mark.owner {
+        // The cell was taken, so the mark was passed back
+        Owner::AddressOwner(addr) if *addr == signer_addr => Ok(PlaceOutcome::Rejected),
+        Owner::AddressOwner(addr) if *addr == multisig.x_address() => {
+            Ok(PlaceOutcome::NextTurn(Player::X))
+        }
+        Owner::AddressOwner(_) => Ok(PlaceOutcome::NextTurn(Player::O)),
+        _ => Err(TicTacToeError::NotAddressOwned(mark_id)),
+    }
+}
+
+fn created_object_owned_by(
+    effects: &SuiTransactionBlockEffects,
+    owner: SuiAddress,
+) -> Option<ObjectID> {
+    effects
+        .all_changed_objects()
+        .iter()
+        .find(|(obj_ref, write_kind)| {
+            matches!(write_kind, WriteKind::Create) && obj_ref.owner == Owner::AddressOwner(owner)
+        })
+        .map(|(obj_ref, _)| obj_ref.object_id())
+}
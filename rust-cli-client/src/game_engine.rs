@@ -0,0 +1,292 @@
+//! Pure Rust port of the game rules of `multisig_tic_tac_toe.move`.
+//! Used for predicting outcomes and validating moves before paying gas for them.
+
+use anyhow::{anyhow, Result};
+use thiserror::Error;
+
+use crate::contract_structs::{Mark, TicTacToe};
+use crate::error::AbortCode;
+use crate::startup::Player;
+
+pub const MARK_EMPTY: u8 = 0;
+pub const MARK_X: u8 = 1;
+pub const MARK_O: u8 = 2;
+
+/// Column major indices of all lines, in the order `get_winner` checks them:
+/// rows, columns, diagonals.
+const LINES: [[u8; 3]; 8] = [
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 4, 8],
+    [6, 4, 2],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+    InProgress,
+    Won(Player),
+    Draw,
+}
+
+impl TryFrom<u8> for GameStatus {
+    type Error = anyhow::Error;
+
+    fn try_from(finished: u8) -> Result<Self> {
+        match finished {
+            0 => Ok(GameStatus::InProgress),
+            1 => Ok(GameStatus::Won(Player::X)),
+            2 => Ok(GameStatus::Won(Player::O)),
+            3 => Ok(GameStatus::Draw),
+            _ => Err(anyhow!("Invalid game state: {}", finished)),
+        }
+    }
+}
+
+/// Failure of a move, as the contract would fail it
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum EngineError {
+    /// The contract aborts with `code`
+    #[error("{code:?}: {reason}")]
+    Abort { code: AbortCode, reason: String },
+    /// The contract fails without an abort code of its own
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl EngineError {
+    fn abort(code: AbortCode, reason: impl Into<String>) -> Self {
+        EngineError::Abort {
+            code,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Result of `place_mark`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceOutcome {
+    /// Cell was already set, the mark is passed back to the same player
+    Rejected,
+    /// Mark was placed and it is now the turn of the contained player
+    NextTurn(Player),
+    Won(Player),
+    Draw,
+}
+
+/// Game state of a `TicTacToe` object
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    /// Column major 3x3 game board
+    pub gameboard: Vec<u8>,
+    /// Index of current turn
+    pub cur_turn: u8,
+    pub finished: u8, // 0 not finished, 1 X Winner, 2 O Winner, 3 Draw
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self {
+            gameboard: vec![MARK_EMPTY; 9],
+            cur_turn: 0,
+            finished: 0,
+        }
+    }
+}
+
+impl From<&TicTacToe> for Board {
+    fn from(game: &TicTacToe) -> Self {
+        Self {
+            gameboard: game.gameboard.clone(),
+            cur_turn: game.cur_turn,
+            finished: game.finished,
+        }
+    }
+}
+
+impl Board {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn status(&self) -> Result<GameStatus> {
+        GameStatus::try_from(self.finished)
+    }
+
+    /// Player whose turn it is, derived from `cur_turn`
+    pub fn cur_player(&self) -> Player {
+        player_of_turn(self.cur_turn)
+    }
+
+    /// Gets column major cell from 3x3 matrix
+    pub fn get_cell(&self, row: u8, col: u8) -> Result<u8, EngineError> {
+        self.get_cell_by_index(get_index(row, col)?)
+    }
+
+    pub fn get_cell_by_index(&self, index: u8) -> Result<u8, EngineError> {
+        if self.gameboard.len() != 9 || index >= 9 {
+            return Err(EngineError::abort(
+                AbortCode::EInvalidSize,
+                format!("Index {} is out of the 3x3 gameboard", index),
+            ));
+        }
+        Ok(self.gameboard[index as usize])
+    }
+
+    /// Whether placing a mark at (row, col) would be accepted by the contract
+    pub fn is_legal_move(&self, row: u8, col: u8) -> bool {
+        self.finished == 0 && matches!(self.get_cell(row, col), Ok(MARK_EMPTY))
+    }
+
+    /// Column major indices of all the empty cells
+    pub fn empty_cells(&self) -> Vec<u8> {
+        (0..9)
+            .filter(|&i| self.gameboard.get(i as usize) == Some(&MARK_EMPTY))
+            .collect()
+    }
+
+    /// Applies a placement exactly as `place_mark` does.
+    pub fn place_mark(&mut self, placement: u8) -> Result<PlaceOutcome, EngineError> {
+        if self.finished != 0 {
+            return Err(EngineError::Failed("Game is already finished".to_string()));
+        }
+        if self.get_cell_by_index(placement)? != MARK_EMPTY {
+            return Ok(PlaceOutcome::Rejected);
+        }
+
+        // Apply turn
+        let player = self.cur_player();
+        self.gameboard[placement as usize] = mark_of(player);
+
+        // Check for winner
+        if let Some(winner) = self.get_winner() {
+            self.finished = match winner {
+                Player::X => 1,
+                Player::O => 2,
+            };
+            return Ok(PlaceOutcome::Won(winner));
+        } else if self.cur_turn >= 8 {
+            self.finished = 3;
+            return Ok(PlaceOutcome::Draw);
+        }
+
+        // Next turn
+        self.cur_turn += 1;
+        Ok(PlaceOutcome::NextTurn(self.cur_player()))
+    }
+
+    /// Same as `get_winner` of the contract, which skips the check before the 5th turn
+    pub fn get_winner(&self) -> Option<Player> {
+        let [m1, m2, m3] = self.winning_line()?.map(|i| self.gameboard[i as usize]);
+        check_for_winner(m1, m2, m3)
+    }
+
+    /// Column major indices of the line `get_winner` finds the winner on, none on a gameboard
+    /// that is not 3x3
+    pub fn winning_line(&self) -> Option<[u8; 3]> {
+        if self.cur_turn < 4 || self.gameboard.len() != 9 {
+            return None;
+        }
+        LINES.iter().copied().find(|line| {
+            let [m1, m2, m3] = line.map(|i| self.gameboard[i as usize]);
+            check_for_winner(m1, m2, m3).is_some()
+        })
+    }
+}
+
+/// Mirrors `send_mark_to_game`; fails when the mark has already been sent.
+pub fn send_mark_to_game(mark: &mut Mark, row: u8, col: u8) -> Result<(), EngineError> {
+    // Mark.during_turn prevents multisig-acc from editing mark.placement after it has been sent to it.
+    if !mark.during_turn {
+        return Err(EngineError::abort(
+            AbortCode::ETriedToCheat,
+            "Mark has already been sent to the game",
+        ));
+    }
+    let index = get_index(row, col)?;
+    if mark.placement.is_some() {
+        return Err(EngineError::Failed(
+            "Mark placement is already filled".to_string(),
+        ));
+    }
+    mark.placement = Some(index);
+    mark.during_turn = false;
+    Ok(())
+}
+
+/// Mirrors `place_mark`, mutating both the game and the mark. On `PlaceOutcome::Rejected` and
+/// `PlaceOutcome::NextTurn` the mark should be passed to the `cur_turn` player, otherwise it is
+/// deleted.
+pub fn place_mark(game: &mut TicTacToe, mark: &mut Mark) -> Result<PlaceOutcome, EngineError> {
+    if mark.game_id.bytes != game.id.id.bytes {
+        return Err(EngineError::abort(
+            AbortCode::EMarkIsFromDifferentGame,
+            format!("Mark belongs to game {}", mark.game_id.bytes),
+        ));
+    }
+    // Note here we empty the option
+    let placement = mark
+        .placement
+        .take()
+        .ok_or(EngineError::Failed("Mark has no placement".to_string()))?;
+
+    let mut board = Board::from(&*game);
+    let outcome = board.place_mark(placement)?;
+    game.gameboard = board.gameboard;
+    game.cur_turn = board.cur_turn;
+    game.finished = board.finished;
+    mark.during_turn = matches!(outcome, PlaceOutcome::Rejected | PlaceOutcome::NextTurn(_));
+    Ok(outcome)
+}
+
+/// Mirrors `delete_game`, which only deletes finished games.
+pub fn delete_game(game: &TicTacToe) -> Result<(), EngineError> {
+    if game.finished == 0 {
+        return Err(EngineError::abort(
+            AbortCode::ETriedToCheat,
+            "Game is not finished",
+        ));
+    }
+    Ok(())
+}
+
+/// Gets column major index from 3x3 matrix
+pub fn get_index(row: u8, col: u8) -> Result<u8, EngineError> {
+    if row >= 3 || col >= 3 {
+        return Err(EngineError::abort(
+            AbortCode::EInvalidSize,
+            format!("Row {} and col {} should be in 0-2", row, col),
+        ));
+    }
+    Ok(col * 3 + row)
+}
+
+pub fn player_of_turn(cur_turn: u8) -> Player {
+    match cur_turn % 2 {
+        0 => Player::X,
+        _ => Player::O,
+    }
+}
+
+pub fn mark_of(player: Player) -> u8 {
+    match player {
+        Player::X => MARK_X,
+        Player::O => MARK_O,
+    }
+}
+
+/// Checks equality of 3 marks and returns the player if they are not empty
+fn check_for_winner(mark_1: u8, mark_2: u8, mark_3: u8) -> Option<Player> {
+    if mark_1 == mark_2 && mark_2 == mark_3 {
+        match mark_1 {
+            MARK_X => Some(Player::X),
+            MARK_O => Some(Player::O),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
@@ -0,0 +1,269 @@
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use futures::{pin_mut, StreamExt};
+
+use sui_types::base_types::{ObjectID, SuiAddress};
+
+use crate::contract_structs::{Mark, TicTacToe};
+use crate::error::TicTacToeError;
+use crate::game_backend::{GameBackend, GameInfo, MoveOutcome};
+use crate::game_engine::{self, Board};
+use crate::game_store::{GameStore, MoveRecord};
+use crate::row_col::{Col, Row};
+use crate::startup::Config;
+use crate::turn_state_machine::{TurnAction, TurnState, TurnStateMachine};
+use crate::turn_watcher::{watch_turns, PollConfig};
+
+/// Failed transactions in a row after which the game is given up
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+
+/// Source of the moves of the current player, e.g. stdin.
+pub trait MoveInput {
+    /// Fails if no move can be made on the gameboard, or if the input of the moves ended
+    fn next_move(&mut self, gameboard: &[u8]) -> Result<(Row, Col)>;
+}
+
+/// Finds the mark of an unfinished game. It is owned by the player of the current turn, or by the
+/// multisig account if it has been sent to the game but not placed yet.
+pub async fn find_game_info<B: GameBackend + Sync>(
+    backend: &B,
+    config: &Config,
+    game: &TicTacToe,
+) -> Result<GameInfo> {
+    let possible_owner = config
+        .multisig
+        .player_address(game_engine::player_of_turn(game.cur_turn));
+    let mark: Mark = match backend.find_mark(game.id.id.bytes, possible_owner).await {
+        Ok(mark) => mark,
+        Err(TicTacToeError::MarkNotFound) => {
+            backend
+                .find_mark(game.id.id.bytes, config.multisig.admin_address())
+                .await?
+        }
+        Err(e) => return Err(e.into()),
+    };
+    Ok(GameInfo {
+        game_id: game.id.id.bytes,
+        mark_id: mark.id.id.bytes,
+    })
+}
+
+/// The objects of a game the player comes back to: as recorded in the store if it was played from
+/// here before, and otherwise looked up
+pub async fn resume_game_info<B: GameBackend + Sync>(
+    backend: &B,
+    config: &Config,
+    game: &TicTacToe,
+    store: Option<&Mutex<GameStore>>,
+) -> Result<GameInfo> {
+    let recorded = store.and_then(|store| {
+        let store = store.lock().unwrap();
+        store
+            .game(config.multisig.admin_address(), game.id.id.bytes)
+            .map(GameInfo::from)
+    });
+    match recorded {
+        Some(game_info) => Ok(game_info),
+        None => find_game_info(backend, config, game).await,
+    }
+}
+
+/// Plays the game until it is finished, waiting for the opponent's moves through the turn watcher.
+/// The game, its last seen version and the moves made are recorded in the store, if any.
+pub async fn play_game<B, I>(
+    backend: &B,
+    config: &Config,
+    game_info: GameInfo,
+    input: &mut I,
+    poll: PollConfig,
+    store: Option<&Mutex<GameStore>>,
+) -> Result<()>
+where
+    B: GameBackend + Sync,
+    I: MoveInput,
+{
+    let game_id = game_info.game_id;
+    let mark_id = game_info.mark_id;
+
+    let player = config.current_player()?;
+    println!("You are playing as {}", player);
+    let multisig_addr = config.multisig.admin_address();
+    record(store, |store| {
+        store.record_game(multisig_addr, &game_info);
+    })?;
+
+    let mut machine = TurnStateMachine::new(
+        player,
+        SuiAddress::from(&config.signer.public()),
+        config.multisig.admin_address(),
+    );
+    let updates = watch_turns(backend, game_info, player, poll);
+    pin_mut!(updates);
+    let mut board = Board::new();
+    let mut failures = 0;
+
+    loop {
+        let res = match machine.next_action() {
+            TurnAction::Observe => {
+                let game = backend.fetch_game(game_id).await?;
+                observe(backend, &mut machine, &game, mark_id).await?;
+                board = Board::from(&game);
+                Ok(())
+            }
+            TurnAction::WaitForUpdate => {
+                let update = updates
+                    .next()
+                    .await
+                    .ok_or(anyhow!("Game updates ended before the game finished"))??;
+                let game = update.game();
+                observe(backend, &mut machine, game, mark_id).await?;
+                record(store, |store| {
+                    store
+                        .record_game(multisig_addr, &game_info)
+                        .last_seen_version = Some(update.version());
+                })?;
+                board = Board::from(game);
+                Ok(())
+            }
+            TurnAction::SendMark => {
+                let (row, col) = input.next_move(&board.gameboard)?;
+                println!("Playing row {}, col {}", row, col);
+                let res = backend
+                    .make_move(&config.signer, &config.multisig, game_id, &board, row, col)
+                    .await;
+                // A move sent before, e.g. by another session, is placed instead
+                if let Err(e @ TicTacToeError::PendingMove { .. }) = &res {
+                    println!("{}, placing it instead", e);
+                    machine.mark_sent();
+                    continue;
+                }
+                if let Ok(outcome) = &res {
+                    let made = MoveRecord {
+                        turn: board.cur_turn,
+                        row: row.into(),
+                        col: col.into(),
+                        rejected: *outcome == MoveOutcome::CellOccupied,
+                    };
+                    record(store, |store| {
+                        store
+                            .record_game(multisig_addr, &game_info)
+                            .moves
+                            .push(made);
+                    })?;
+                }
+                res.map(|outcome| match outcome {
+                    MoveOutcome::CellOccupied => {
+                        println!("{}", outcome);
+                        machine.move_rejected();
+                    }
+                    _ => {
+                        machine.mark_sent();
+                        machine.place_submitted();
+                    }
+                })
+            }
+            TurnAction::PlaceMark => {
+                let res = backend
+                    .place_mark(&config.signer, &config.multisig, game_id, mark_id)
+                    .await;
+                res.map(|_| {
+                    machine.place_submitted();
+                })
+            }
+            TurnAction::Done => return Ok(()),
+        };
+
+        match res {
+            Ok(()) => failures = 0,
+            Err(e) => {
+                failures += 1;
+                if failures >= MAX_CONSECUTIVE_FAILURES {
+                    return Err(e.into());
+                }
+                println!("Transaction failed, retrying: {}", e);
+                machine.transaction_failed();
+            }
+        }
+    }
+}
+
+/// Applies a change to the store and saves it, if there is a store
+fn record(store: Option<&Mutex<GameStore>>, change: impl FnOnce(&mut GameStore)) -> Result<()> {
+    let Some(store) = store else {
+        return Ok(());
+    };
+    let mut store = store.lock().unwrap();
+    change(&mut store);
+    store.save()
+}
+
+/// Feeds an observation of the game to the state machine, and tells the player about the new state
+async fn observe<B: GameBackend + Sync>(
+    backend: &B,
+    machine: &mut TurnStateMachine,
+    game: &TicTacToe,
+    mark_id: ObjectID,
+) -> Result<()> {
+    let mark_owner = match backend.mark_owner(mark_id).await {
+        Ok(owner) => Some(owner),
+        // The mark is deleted at the end of the game
+        Err(TicTacToeError::MarkNotFound) => None,
+        Err(e) => return Err(e.into()),
+    };
+    let prev_state = machine.state();
+    let state = machine.observe(game, mark_owner)?;
+    if state == prev_state {
+        return Ok(());
+    }
+    match state {
+        TurnState::AwaitingOpponent => {
+            print_gameboard(&game.gameboard)?;
+            println!("Waiting for opponnent...");
+        }
+        TurnState::MyTurnNeedInput => print_gameboard(&game.gameboard)?,
+        TurnState::Finished(outcome) => {
+            let winning_line = Board::from(game).winning_line().map(Vec::from);
+            print_gameboard_highlighted(&game.gameboard, &winning_line.unwrap_or_default())?;
+            println!("{}", outcome);
+            println!("Replay it with: replay {}", game.id.id.bytes);
+        }
+        TurnState::MarkSentAwaitingPlace | TurnState::PlaceSubmitted | TurnState::Recovering => {}
+    }
+    Ok(())
+}
+
+pub fn print_gameboard(gameboard: &[u8]) -> Result<()> {
+    print_gameboard_highlighted(gameboard, &[])
+}
+
+/// Prints the gameboard with the marks of the `highlighted` column major cells between arrows,
+/// e.g. the winning line
+pub fn print_gameboard_highlighted(gameboard: &[u8], highlighted: &[u8]) -> Result<()> {
+    println!("     0     1     2");
+    print!("  ┌-----┬-----┬-----┐");
+    for row in 0..3 {
+        println!();
+        print!("{} ", row);
+        for col in 0..3 {
+            let mark = match gameboard[col * 3 + row] {
+                0 => " ",
+                1 => "X",
+                2 => "O",
+                _ => {
+                    return Err(anyhow!("Unexpected cell value"));
+                }
+            };
+            if highlighted.contains(&((col * 3 + row) as u8)) {
+                print!("| >{}< ", mark)
+            } else {
+                print!("|  {}  ", mark)
+            }
+        }
+        println!("|");
+        print!("  ├-----┼-----┼-----┤");
+    }
+    print!("\r");
+    println!("  └-----┴-----┴-----┘");
+    Ok(())
+}
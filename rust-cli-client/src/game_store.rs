@@ -0,0 +1,198 @@
+//! Local record of the games of each multisig account on each network, so that `play` resumes
+//! without looking the game and its mark up again, and can reconcile the transactions which were
+//! pending when the CLI stopped.
+//!
+//! Kept as JSON in `<data_dir>/multisig-tic-tac-toe/games.json`, eg.
+//! ```json
+//! {
+//!   "testnet": {
+//!     "0x5a3...": {
+//!       "games": [{ "game_id": "0x1f0...", "mark_id": "0x8c2...", "last_seen_version": 12, "moves": [] }],
+//!       "pending": ["6Ruu..."]
+//!     }
+//!   }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest};
+
+use crate::game_backend::GameInfo;
+use crate::network_config::{app_data_dir, Network};
+
+const GAMES_FILE_NAME: &str = "games.json";
+
+/// A move made from this client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveRecord {
+    /// Turn of the game during which the move was made
+    pub turn: u8,
+    pub row: u8,
+    pub col: u8,
+    /// Whether the contract passed the mark back as the cell was taken
+    pub rejected: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub game_id: ObjectID,
+    pub mark_id: ObjectID,
+    /// Version of the game when it was last observed
+    pub last_seen_version: Option<SequenceNumber>,
+    /// In the order they were made
+    pub moves: Vec<MoveRecord>,
+    /// Whether the game was given up from here, as the contract cannot end it early
+    #[serde(default)]
+    pub resigned: bool,
+}
+
+impl From<&GameRecord> for GameInfo {
+    fn from(record: &GameRecord) -> Self {
+        GameInfo {
+            game_id: record.game_id,
+            mark_id: record.mark_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountRecord {
+    pub games: Vec<GameRecord>,
+    /// Transactions which were submitted, but whose outcome is not known yet
+    pub pending: Vec<TransactionDigest>,
+}
+
+/// Records of the multisig accounts, by address
+type Accounts = BTreeMap<String, AccountRecord>;
+
+/// Records of the multisig accounts on the network the CLI runs on. The records of the other
+/// networks are kept as they are, as the same account has other objects there.
+#[derive(Debug)]
+pub struct GameStore {
+    path: PathBuf,
+    network: String,
+    networks: BTreeMap<String, Accounts>,
+}
+
+impl GameStore {
+    pub fn default_path() -> Option<PathBuf> {
+        app_data_dir().map(|dir| dir.join(GAMES_FILE_NAME))
+    }
+
+    /// A missing file is the same as an empty store
+    pub fn load(path: &Path, network: Network) -> Result<Self> {
+        let networks = if path.exists() {
+            let contents = fs::read_to_string(path)
+                .with_context(|| format!("Could not read game store {}", path.display()))?;
+            serde_json::from_str(&contents)
+                .with_context(|| format!("Could not parse game store {}", path.display()))?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            network: network.to_string(),
+            networks,
+        })
+    }
+
+    /// Writes the store to a temporary file first, so that a crash never leaves it half written
+    pub fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Could not create directory {}", dir.display()))?;
+        }
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(&self.networks)?)
+            .with_context(|| format!("Could not write game store {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("Could not write game store {}", self.path.display()))
+    }
+
+    pub fn account(&self, multisig: SuiAddress) -> Option<&AccountRecord> {
+        self.networks.get(&self.network)?.get(&multisig.to_string())
+    }
+
+    /// The record of an account, only if there is one already
+    fn existing_account_mut(&mut self, multisig: SuiAddress) -> Option<&mut AccountRecord> {
+        self.networks
+            .get_mut(&self.network)?
+            .get_mut(&multisig.to_string())
+    }
+
+    fn account_mut(&mut self, multisig: SuiAddress) -> &mut AccountRecord {
+        self.networks
+            .entry(self.network.clone())
+            .or_default()
+            .entry(multisig.to_string())
+            .or_default()
+    }
+
+    pub fn game(&self, multisig: SuiAddress, game_id: ObjectID) -> Option<&GameRecord> {
+        self.account(multisig)?
+            .games
+            .iter()
+            .find(|game| game.game_id == game_id)
+    }
+
+    /// Whether the game was given up from here
+    pub fn is_resigned(&self, multisig: SuiAddress, game_id: ObjectID) -> bool {
+        self.game(multisig, game_id)
+            .is_some_and(|game| game.resigned)
+    }
+
+    /// The record of a game, created if it is not recorded yet
+    pub fn record_game(&mut self, multisig: SuiAddress, game_info: &GameInfo) -> &mut GameRecord {
+        let games = &mut self.account_mut(multisig).games;
+        let index = match games
+            .iter()
+            .position(|game| game.game_id == game_info.game_id)
+        {
+            Some(index) => index,
+            None => {
+                games.push(GameRecord {
+                    game_id: game_info.game_id,
+                    mark_id: game_info.mark_id,
+                    last_seen_version: None,
+                    moves: vec![],
+                    resigned: false,
+                });
+                games.len() - 1
+            }
+        };
+        &mut games[index]
+    }
+
+    pub fn remove_game(&mut self, multisig: SuiAddress, game_id: ObjectID) {
+        if let Some(account) = self.existing_account_mut(multisig) {
+            account.games.retain(|game| game.game_id != game_id);
+        }
+    }
+
+    pub fn add_pending(&mut self, multisig: SuiAddress, digest: TransactionDigest) {
+        let pending = &mut self.account_mut(multisig).pending;
+        if !pending.contains(&digest) {
+            pending.push(digest);
+        }
+    }
+
+    pub fn remove_pending(&mut self, multisig: SuiAddress, digest: TransactionDigest) {
+        if let Some(account) = self.existing_account_mut(multisig) {
+            account.pending.retain(|pending| *pending != digest);
+        }
+    }
+
+    /// Forgets the pending transactions of the account, to reconcile them
+    pub fn take_pending(&mut self, multisig: SuiAddress) -> Vec<TransactionDigest> {
+        match self.existing_account_mut(multisig) {
+            Some(account) => std::mem::take(&mut account.pending),
+            None => vec![],
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Picks the gas coins of the transactions, so that concurrent transactions never pay with the same
+//! coin, which would lock it until the end of the epoch.
+//!
+//! Coins are reserved while their transaction is in flight. Every game gets a dedicated coin of the
+//! player paying for its transactions, split from a bigger coin if needed, so that games played at
+//! the same time do not compete for coins. Dust coins, too small to pay for a transaction, are
+//! merged.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+
+/// Budgets a dedicated coin is split for
+pub const DEDICATED_COIN_TXS: u64 = 20;
+/// Number of dust coins from which they are merged
+pub const MIN_DUST_TO_MERGE: usize = 2;
+/// Gas coins a transaction can pay with
+const MAX_GAS_COINS: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasCoinInfo {
+    pub object_ref: ObjectRef,
+    pub balance: u64,
+}
+
+impl GasCoinInfo {
+    pub fn id(&self) -> ObjectID {
+        self.object_ref.0
+    }
+}
+
+/// Coins dedicated to the transactions of a game are owned by the player paying for them, and the
+/// game is identified by one of its objects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GasKey {
+    pub owner: SuiAddress,
+    pub game_object: ObjectID,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasPlan {
+    /// Pay with the coin
+    Use(ObjectRef),
+    /// Split a coin of `amount` out of `from`, which is then dedicated to the game
+    Split { from: ObjectRef, amount: u64 },
+}
+
+/// Dust coins to merge into `primary`, by paying for a transaction with all of them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergePlan {
+    pub primary: ObjectRef,
+    pub dust: Vec<ObjectRef>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Coins of the transactions in flight
+    reserved: BTreeSet<ObjectID>,
+    dedicated: BTreeMap<GasKey, ObjectID>,
+}
+
+#[derive(Debug, Default)]
+pub struct GasManager {
+    state: Mutex<State>,
+}
+
+/// Reservation of a coin for a transaction in flight, released when dropped
+#[derive(Debug)]
+pub struct GasReservation<'a> {
+    manager: &'a GasManager,
+    object_ref: ObjectRef,
+}
+
+impl GasReservation<'_> {
+    pub fn object_ref(&self) -> ObjectRef {
+        self.object_ref
+    }
+}
+
+impl Drop for GasReservation<'_> {
+    fn drop(&mut self) {
+        self.manager
+            .state
+            .lock()
+            .unwrap()
+            .reserved
+            .remove(&self.object_ref.0);
+    }
+}
+
+impl GasManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How to pay a transaction of `budget` with the `coins` of the payer, without the `excluded`
+    /// ones. `None` if no coin can pay for it.
+    pub fn plan(
+        &self,
+        key: Option<GasKey>,
+        coins: &[GasCoinInfo],
+        budget: u64,
+        excluded: &[ObjectID],
+    ) -> Option<GasPlan> {
+        let mut state = self.state.lock().unwrap();
+        let available = |state: &State, coin: &GasCoinInfo| {
+            !state.reserved.contains(&coin.id()) && !excluded.contains(&coin.id())
+        };
+
+        if let Some(key) = key {
+            if let Some(dedicated) = state.dedicated.get(&key).copied() {
+                match coins.iter().find(|coin| coin.id() == dedicated) {
+                    Some(coin) if coin.balance >= budget => {
+                        if available(&state, coin) {
+                            return Some(GasPlan::Use(coin.object_ref));
+                        }
+                    }
+                    // Spent or merged, so another one is dedicated
+                    _ => {
+                        state.dedicated.remove(&key);
+                    }
+                }
+            }
+        }
+
+        let dedicated: BTreeSet<ObjectID> = state.dedicated.values().copied().collect();
+        let mut free: Vec<&GasCoinInfo> = coins
+            .iter()
+            .filter(|coin| available(&state, coin))
+            .filter(|coin| !dedicated.contains(&coin.id()) && coin.balance >= budget)
+            .collect();
+        free.sort_by_key(|coin| coin.balance);
+
+        let split_amount = budget * DEDICATED_COIN_TXS;
+        match free.as_slice() {
+            [] => None,
+            // Keep the only coin for the other games, if it is big enough to split
+            [coin] if key.is_some() && coin.balance >= split_amount + budget => {
+                Some(GasPlan::Split {
+                    from: coin.object_ref,
+                    amount: split_amount,
+                })
+            }
+            // The smallest coin which can pay for it, keeping the bigger ones to split
+            [coin, ..] => Some(GasPlan::Use(coin.object_ref)),
+        }
+    }
+
+    /// Reserves a coin for a transaction, dedicating it to the game of `key`. `None` if it is
+    /// already reserved.
+    pub fn reserve(
+        &self,
+        object_ref: ObjectRef,
+        key: Option<GasKey>,
+    ) -> Option<GasReservation<'_>> {
+        let mut state = self.state.lock().unwrap();
+        if !state.reserved.insert(object_ref.0) {
+            return None;
+        }
+        if let Some(key) = key {
+            state.dedicated.insert(key, object_ref.0);
+        }
+        Some(GasReservation {
+            manager: self,
+            object_ref,
+        })
+    }
+
+    /// Dust coins worth merging, into the biggest coin which is neither reserved nor dedicated,
+    /// without the `excluded` ones
+    pub fn merge_plan(
+        &self,
+        coins: &[GasCoinInfo],
+        budget: u64,
+        excluded: &[ObjectID],
+    ) -> Option<MergePlan> {
+        let state = self.state.lock().unwrap();
+        let dedicated: BTreeSet<ObjectID> = state.dedicated.values().copied().collect();
+        let mut mergeable: Vec<&GasCoinInfo> = coins
+            .iter()
+            .filter(|coin| !state.reserved.contains(&coin.id()) && !dedicated.contains(&coin.id()))
+            .filter(|coin| !excluded.contains(&coin.id()))
+            .collect();
+        mergeable.sort_by_key(|coin| std::cmp::Reverse(coin.balance));
+
+        let (primary, rest) = mergeable.split_first()?;
+        let dust: Vec<&GasCoinInfo> = rest
+            .iter()
+            .filter(|coin| coin.balance < budget)
+            .take(MAX_GAS_COINS - 1)
+            .copied()
+            .collect();
+        let total: u64 = primary.balance + dust.iter().map(|coin| coin.balance).sum::<u64>();
+        // The merge is paid for by the merged coins
+        if dust.len() < MIN_DUST_TO_MERGE || total < budget {
+            return None;
+        }
+        Some(MergePlan {
+            primary: primary.object_ref,
+            dust: dust.iter().map(|coin| coin.object_ref).collect(),
+        })
+    }
+}
@@ -0,0 +1,96 @@
+//! Move history of a game, rebuilt from its transactions, as the `TicTacToe` object only keeps the
+//! current gameboard.
+//!
+//! Every move goes through the mark: `send_mark_to_game` fills its placement from the row and col
+//! of the move, and `place_mark` applies the placement to the game. Both mutate the mark, so the
+//! version of the mark they take as input orders them, even within a checkpoint.
+
+use anyhow::{anyhow, Result};
+
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest};
+
+use crate::game_engine::{get_index, Board, PlaceOutcome};
+use crate::startup::Player;
+use crate::tic_tac_toe_client::TicTacToeClient;
+
+/// Call of the contract taking the mark of the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkCall {
+    Send { row: u8, col: u8 },
+    Place,
+}
+
+/// Successful transaction sending or placing the mark of the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkTransaction {
+    pub digest: TransactionDigest,
+    pub sender: SuiAddress,
+    pub timestamp_ms: Option<u64>,
+    /// Version of the mark taken as input
+    pub mark_version: SequenceNumber,
+    pub call: MarkCall,
+}
+
+/// A placed mark, or one which bounced back as its cell was taken
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveEntry {
+    /// Turn during which the move was made
+    pub turn: u8,
+    pub player: Player,
+    pub row: u8,
+    pub col: u8,
+    /// Account which sent the mark, the player's own or the multisig account
+    pub signer: SuiAddress,
+    /// Transaction which placed the mark
+    pub digest: TransactionDigest,
+    pub timestamp_ms: Option<u64>,
+    pub outcome: PlaceOutcome,
+    /// Game after the move, unchanged by a rejected one
+    pub board: Board,
+}
+
+impl MoveEntry {
+    pub fn is_rejected(&self) -> bool {
+        self.outcome == PlaceOutcome::Rejected
+    }
+}
+
+/// Replays the transactions of the mark, in any order, from an empty gameboard. A mark sent but not
+/// placed yet is not part of the history.
+pub fn rebuild_history(mut txs: Vec<MarkTransaction>) -> Result<Vec<MoveEntry>> {
+    txs.sort_by_key(|tx| tx.mark_version);
+    let mut board = Board::new();
+    let mut sent = None;
+    let mut moves = vec![];
+    for tx in txs {
+        match tx.call {
+            MarkCall::Send { row, col } => sent = Some((row, col, tx.sender)),
+            MarkCall::Place => {
+                let (row, col, signer) = sent
+                    .take()
+                    .ok_or(anyhow!("Mark placed by {} was not sent", tx.digest))?;
+                let turn = board.cur_turn;
+                let player = board.cur_player();
+                let outcome = board.place_mark(get_index(row, col)?)?;
+                moves.push(MoveEntry {
+                    turn,
+                    player,
+                    row,
+                    col,
+                    signer,
+                    digest: tx.digest,
+                    timestamp_ms: tx.timestamp_ms,
+                    outcome,
+                    board: board.clone(),
+                });
+            }
+        }
+    }
+    Ok(moves)
+}
+
+/// Queries the transactions of the game and rebuilds its moves
+pub async fn fetch_history(client: &TicTacToeClient, game_id: ObjectID) -> Result<Vec<MoveEntry>> {
+    let txs = client.mark_transactions(game_id).await?;
+    rebuild_history(txs)
+}
@@ -0,0 +1,461 @@
+//! A local stand-in for a Sui network, which keeps the contract objects in memory and transfers
+//! them between owners exactly as `multisig_tic_tac_toe.move` does.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::future;
+use tokio::sync::watch;
+
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest};
+use sui_types::crypto::SuiKeyPair;
+use sui_types::id::{ID, UID};
+
+use crate::contract_structs::{Mark, TicTacToe, TicTacToeTrophy};
+use crate::error::TicTacToeError;
+use crate::game_backend::{GameBackend, GameInfo};
+use crate::game_engine::{self, EngineError, PlaceOutcome, MARK_EMPTY};
+use crate::multi_sig_1_out_of_n::MultiSig1OutOfN;
+use crate::pagination::{paginate_from, take_page};
+use crate::row_col::{Col, Row};
+use crate::startup::Player;
+use crate::tic_tac_toe_client::{FetchGameFilter, Result, TrophyPage};
+
+/// Number of objects in the pages of owned objects, smaller than on a Sui node so that following
+/// the cursors is exercised with few objects
+const PAGE_SIZE: usize = 2;
+
+#[derive(Debug, Clone)]
+pub enum ContractObject {
+    TicTacToe(TicTacToe),
+    Mark(Mark),
+    TicTacToeTrophy(TicTacToeTrophy),
+}
+
+#[derive(Debug, Clone)]
+struct OwnedObject {
+    owner: SuiAddress,
+    version: SequenceNumber,
+    object: ContractObject,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    objects: BTreeMap<ObjectID, OwnedObject>,
+    /// Used for deriving fresh object ids, versions and transaction digests
+    counter: u64,
+}
+
+#[derive(Debug)]
+pub struct InMemoryBackend {
+    state: Mutex<State>,
+    /// Counter of the last executed transaction
+    changes: watch::Sender<u64>,
+}
+
+impl Default for InMemoryBackend {
+    fn default() -> Self {
+        Self {
+            state: Mutex::default(),
+            changes: watch::channel(0).0,
+        }
+    }
+}
+
+impl InMemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn owner(&self, object_id: ObjectID) -> Option<SuiAddress> {
+        self.state
+            .lock()
+            .unwrap()
+            .objects
+            .get(&object_id)
+            .map(|o| o.owner)
+    }
+
+    /// All the contract objects owned by an address
+    pub fn owned_objects(&self, owner: SuiAddress) -> Vec<ContractObject> {
+        self.state
+            .lock()
+            .unwrap()
+            .objects
+            .values()
+            .filter(|o| o.owner == owner)
+            .map(|o| o.object.clone())
+            .collect()
+    }
+
+    pub fn trophies(&self, owner: SuiAddress) -> Vec<TicTacToeTrophy> {
+        self.owned_objects(owner)
+            .into_iter()
+            .filter_map(|o| match o {
+                ContractObject::TicTacToeTrophy(trophy) => Some(trophy),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Adds a game as it is on chain, owned by the multisig account, e.g. from its notation. The
+    /// mark of an unfinished game is passed to the player of the current turn. A finished game has
+    /// no mark, as the contract deletes it, so it cannot be played on and none is returned.
+    pub fn import_game(
+        &self,
+        game: TicTacToe,
+        multisig_addr: SuiAddress,
+    ) -> Result<Option<GameInfo>> {
+        let game_id = game.id.id.bytes;
+        let (game_info, _) = self.execute(|state| {
+            if state.objects.contains_key(&game_id) {
+                return Err(TicTacToeError::ExecutionFailed(format!(
+                    "Game {} already exists",
+                    game_id
+                )));
+            }
+            let mut game_info = None;
+            if game.finished == 0 {
+                let mark_uid = state.new_uid();
+                game_info = Some(GameInfo {
+                    game_id,
+                    mark_id: mark_uid.id.bytes,
+                });
+                let addr = match game_engine::player_of_turn(game.cur_turn) {
+                    Player::X => game.x_addr,
+                    Player::O => game.o_addr,
+                };
+                let mark = Mark {
+                    id: mark_uid,
+                    placement: None,
+                    during_turn: true,
+                    game_owners: multisig_addr,
+                    game_id: ID { bytes: game_id },
+                };
+                state.transfer(ContractObject::Mark(mark), addr);
+            }
+            state.transfer(ContractObject::TicTacToe(game), multisig_addr);
+            Ok(game_info)
+        })?;
+        Ok(game_info)
+    }
+
+    /// Runs a transaction against a copy of the state, which is only committed on success.
+    fn execute<T>(
+        &self,
+        tx: impl FnOnce(&mut State) -> Result<T>,
+    ) -> Result<(T, TransactionDigest)> {
+        let mut state = self.state.lock().unwrap();
+        let mut tx_state = State {
+            objects: state.objects.clone(),
+            counter: state.counter,
+        };
+        let res = tx(&mut tx_state)?;
+        let counter = tx_state.next_counter();
+        *state = tx_state;
+        self.changes.send_replace(counter);
+        Ok((res, TransactionDigest::new(counter_bytes(counter))))
+    }
+}
+
+impl State {
+    fn next_counter(&mut self) -> u64 {
+        self.counter += 1;
+        self.counter
+    }
+
+    fn new_uid(&mut self) -> UID {
+        let bytes = ObjectID::new(counter_bytes(self.next_counter()));
+        UID { id: ID { bytes } }
+    }
+
+    /// Takes an object out of storage, as passing an owned object by value to a move call does
+    fn take_owned(&mut self, object_id: ObjectID, sender: SuiAddress) -> Result<ContractObject> {
+        let obj = self.objects.get(&object_id).ok_or_else(|| {
+            TicTacToeError::ExecutionFailed(format!("Object {} does not exist", object_id))
+        })?;
+        if obj.owner != sender {
+            return Err(TicTacToeError::ExecutionFailed(format!(
+                "Object {} is not owned by {}",
+                object_id, sender
+            )));
+        }
+        Ok(self.objects.remove(&object_id).unwrap().object)
+    }
+
+    /// Also bumps the version of the object, as every object a transaction mutates gets a new one
+    fn transfer(&mut self, object: ContractObject, owner: SuiAddress) {
+        let object_id = match &object {
+            ContractObject::TicTacToe(game) => game.id.id.bytes,
+            ContractObject::Mark(mark) => mark.id.id.bytes,
+            ContractObject::TicTacToeTrophy(trophy) => trophy.id,
+        };
+        let version = SequenceNumber::from_u64(self.next_counter());
+        self.objects.insert(
+            object_id,
+            OwnedObject {
+                owner,
+                version,
+                object,
+            },
+        );
+    }
+}
+
+#[async_trait]
+impl GameBackend for InMemoryBackend {
+    async fn create_game(
+        &self,
+        signer: &SuiKeyPair,
+        multisig: &MultiSig1OutOfN,
+    ) -> Result<GameInfo> {
+        let sender = multisig_sender(signer, multisig)?;
+        let x_addr = multisig.x_address();
+        let o_addr = multisig.o_address();
+
+        let (game_info, _) = self.execute(|state| {
+            let id = state.new_uid();
+            let game_id = id.id.bytes;
+            let mark_id = state.new_uid();
+            let mark_obj_id = mark_id.id.bytes;
+            let game = TicTacToe {
+                id,
+                gameboard: vec![MARK_EMPTY; 9],
+                cur_turn: 0,
+                x_addr,
+                o_addr,
+                finished: 0,
+            };
+            let mark = Mark {
+                id: mark_id,
+                placement: None,
+                during_turn: true, // Mark is passed to x_addr
+                game_owners: sender,
+                game_id: ID { bytes: game_id },
+            };
+            state.transfer(ContractObject::TicTacToe(game), sender);
+            state.transfer(ContractObject::Mark(mark), x_addr);
+            Ok(GameInfo {
+                game_id,
+                mark_id: mark_obj_id,
+            })
+        })?;
+        Ok(game_info)
+    }
+
+    async fn send_mark_to_game(
+        &self,
+        signer: &SuiKeyPair,
+        mark_id: ObjectID,
+        row: Row,
+        col: Col,
+    ) -> Result<TransactionDigest> {
+        let sender = SuiAddress::from(&signer.public());
+        let (_, digest) = self.execute(|state| {
+            let ContractObject::Mark(mut mark) = state.take_owned(mark_id, sender)? else {
+                return Err(not_a(mark_id, "Mark"));
+            };
+            game_engine::send_mark_to_game(&mut mark, row.into(), col.into())
+                .map_err(|e| contract_error("send_mark_to_game", e))?;
+            let game_owners = mark.game_owners;
+            state.transfer(ContractObject::Mark(mark), game_owners);
+            Ok(())
+        })?;
+        Ok(digest)
+    }
+
+    async fn place_mark(
+        &self,
+        signer: &SuiKeyPair,
+        multisig: &MultiSig1OutOfN,
+        game_id: ObjectID,
+        mark_id: ObjectID,
+    ) -> Result<PlaceOutcome> {
+        let sender = multisig_sender(signer, multisig)?;
+        let (outcome, _) = self.execute(|state| {
+            let ContractObject::TicTacToe(mut game) = state.take_owned(game_id, sender)? else {
+                return Err(not_a(game_id, "TicTacToe"));
+            };
+            let ContractObject::Mark(mut mark) = state.take_owned(mark_id, sender)? else {
+                return Err(not_a(mark_id, "Mark"));
+            };
+
+            let outcome = game_engine::place_mark(&mut game, &mut mark)
+                .map_err(|e| contract_error("place_mark", e))?;
+            match outcome {
+                PlaceOutcome::Rejected | PlaceOutcome::NextTurn(_) => {
+                    let addr = match game_engine::player_of_turn(game.cur_turn) {
+                        Player::X => game.x_addr,
+                        Player::O => game.o_addr,
+                    };
+                    state.transfer(ContractObject::Mark(mark), addr);
+                }
+                PlaceOutcome::Won(player) => {
+                    let (winner, loser) = match player {
+                        Player::X => (game.x_addr, game.o_addr),
+                        Player::O => (game.o_addr, game.x_addr),
+                    };
+                    let trophy = TicTacToeTrophy {
+                        id: state.new_uid().id.bytes,
+                        winner,
+                        loser,
+                        played_as: game_engine::mark_of(player),
+                        game_id,
+                    };
+                    state.transfer(ContractObject::TicTacToeTrophy(trophy), winner);
+                    // Mark is deleted
+                }
+                PlaceOutcome::Draw => {
+                    // Mark is deleted
+                }
+            }
+            state.transfer(ContractObject::TicTacToe(game), sender);
+            Ok(outcome)
+        })?;
+        Ok(outcome)
+    }
+
+    async fn delete_game(
+        &self,
+        signer: &SuiKeyPair,
+        multisig: &MultiSig1OutOfN,
+        game_id: ObjectID,
+    ) -> Result<TransactionDigest> {
+        let sender = multisig_sender(signer, multisig)?;
+        let (_, digest) = self.execute(|state| {
+            let ContractObject::TicTacToe(game) = state.take_owned(game_id, sender)? else {
+                return Err(not_a(game_id, "TicTacToe"));
+            };
+            game_engine::delete_game(&game).map_err(|e| contract_error("delete_game", e))
+        })?;
+        Ok(digest)
+    }
+
+    async fn fetch_available_game(
+        &self,
+        multisig: &MultiSig1OutOfN,
+        fetch_filter: Option<&FetchGameFilter>,
+    ) -> Result<TicTacToe> {
+        self.owned_objects(multisig.admin_address())
+            .into_iter()
+            .find_map(|obj| {
+                let ContractObject::TicTacToe(game) = obj else {
+                    return None;
+                };
+                if let Some(fetch_filter) = fetch_filter {
+                    fetch_filter.filter(game)
+                } else {
+                    Some(game)
+                }
+            })
+            .ok_or(TicTacToeError::NoAvailableGames)
+    }
+
+    async fn fetch_game(&self, game_id: ObjectID) -> Result<TicTacToe> {
+        match self.state.lock().unwrap().objects.get(&game_id) {
+            Some(OwnedObject {
+                object: ContractObject::TicTacToe(game),
+                ..
+            }) => Ok(game.clone()),
+            _ => Err(TicTacToeError::GameNotFound(game_id)),
+        }
+    }
+
+    async fn find_mark(&self, game_id: ObjectID, owner_addr: SuiAddress) -> Result<Mark> {
+        self.owned_objects(owner_addr)
+            .into_iter()
+            .find_map(|obj| match obj {
+                ContractObject::Mark(mark) if mark.game_id.bytes == game_id => Some(mark),
+                _ => None,
+            })
+            .ok_or(TicTacToeError::MarkNotFound)
+    }
+
+    async fn mark_owner(&self, mark_id: ObjectID) -> Result<SuiAddress> {
+        self.owner(mark_id).ok_or(TicTacToeError::MarkNotFound)
+    }
+
+    async fn game_version(&self, game_id: ObjectID) -> Result<SequenceNumber> {
+        self.state
+            .lock()
+            .unwrap()
+            .objects
+            .get(&game_id)
+            .map(|o| o.version)
+            .ok_or(TicTacToeError::GameNotFound(game_id))
+    }
+
+    async fn fetch_trophy(&self, trophy_id: ObjectID) -> Result<TicTacToeTrophy> {
+        match self.state.lock().unwrap().objects.get(&trophy_id) {
+            Some(OwnedObject {
+                object: ContractObject::TicTacToeTrophy(trophy),
+                ..
+            }) => Ok(trophy.clone()),
+            _ => Err(TicTacToeError::TrophyNotFound(trophy_id)),
+        }
+    }
+
+    /// Trophies are in the order of their ids, as the objects owned by an address on a Sui node,
+    /// and are fetched in pages of `PAGE_SIZE` as the Sui client does
+    async fn fetch_trophies(
+        &self,
+        owner_addr: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> Result<TrophyPage> {
+        let trophies = self.trophies(owner_addr);
+        let trophies = paginate_from(cursor, |page_cursor| {
+            let after: Vec<TicTacToeTrophy> = trophies
+                .iter()
+                .filter(|trophy| page_cursor.is_none_or(|cursor| trophy.id > cursor))
+                .cloned()
+                .collect();
+            let data: Vec<TicTacToeTrophy> = after.iter().take(PAGE_SIZE).cloned().collect();
+            future::ready(Ok(TrophyPage {
+                next_cursor: data.last().map(|trophy| trophy.id),
+                has_next_page: after.len() > data.len(),
+                data,
+            }))
+        });
+        take_page(trophies, limit, |trophy| trophy.id).await
+    }
+
+    /// Notifies of every executed transaction, whichever game it changed
+    fn subscribe_changes(&self, _game_info: &GameInfo) -> Option<watch::Receiver<u64>> {
+        Some(self.changes.subscribe())
+    }
+}
+
+/// Multisig transactions can only be signed by one of the participants of the multisig.
+fn multisig_sender(signer: &SuiKeyPair, multisig: &MultiSig1OutOfN) -> Result<SuiAddress> {
+    if !multisig.contains(&signer.public()) {
+        return Err(TicTacToeError::Signing(anyhow!(
+            "Signer is not part of the multisig"
+        )));
+    }
+    Ok(multisig.admin_address())
+}
+
+/// Error of a call of the contract, as the aborts are reported by a Sui node
+fn contract_error(function: &str, error: EngineError) -> TicTacToeError {
+    match error {
+        EngineError::Abort { code, .. } => TicTacToeError::MoveAbort {
+            code,
+            function: Some(function.to_string()),
+        },
+        EngineError::Failed(reason) => TicTacToeError::ExecutionFailed(reason),
+    }
+}
+
+/// Objects passed to a call must be of the type of its parameter
+fn not_a(object_id: ObjectID, struct_name: &str) -> TicTacToeError {
+    TicTacToeError::ExecutionFailed(format!("Object {} is not a {}", object_id, struct_name))
+}
+
+fn counter_bytes(counter: u64) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes[24..].copy_from_slice(&counter.to_be_bytes());
+    bytes
+}
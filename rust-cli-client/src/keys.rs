@@ -0,0 +1,165 @@
+//! Where the signer's private key and the opponent's public key come from.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{stdin, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::Args;
+
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{PublicKey, SuiKeyPair};
+
+use crate::network_config::app_config_dir;
+
+const ADDRESS_BOOK_FILE_NAME: &str = "address_book.toml";
+
+/// Exactly one source of the signer's private key, or only their public key for the commands which
+/// do not sign
+#[derive(Debug, Clone, Args)]
+#[group(required = true, multiple = false)]
+pub struct SignerOpts {
+    /// Address or alias of your key in the Sui keystore
+    #[arg(short, long)]
+    pub signer: Option<String>,
+    /// File containing your private key in base64
+    #[arg(long)]
+    pub key_file: Option<PathBuf>,
+    /// Prompt for your private key in base64, without echoing it, or read it from piped stdin
+    #[arg(long)]
+    pub key_stdin: bool,
+    /// Your public key in base64, when your private key is held elsewhere, to export transactions
+    /// for it to sign or to submit them
+    #[arg(long)]
+    pub public_key: Option<String>,
+}
+
+impl SignerOpts {
+    pub fn load_signer(&self, keystore_path: Option<&Path>) -> Result<SuiKeyPair> {
+        if let Some(signer) = &self.signer {
+            let keystore = open_keystore(keystore_path)?;
+            let address = keystore_address(&keystore, signer)?;
+            Ok(keystore.get_key(&address)?.copy())
+        } else if let Some(path) = &self.key_file {
+            let private_key = fs::read_to_string(path)
+                .with_context(|| format!("Could not read key file {}", path.display()))?;
+            parse_private_key(&private_key)
+        } else if self.key_stdin {
+            // Not echoed when typed in, but it can also be piped in
+            let private_key = if stdin().is_terminal() {
+                rpassword::prompt_password("Enter your private key in base64: ")?
+            } else {
+                let mut private_key = String::new();
+                stdin().read_line(&mut private_key)?;
+                private_key
+            };
+            parse_private_key(&private_key)
+        } else {
+            bail!(
+                "Your private key is needed, --public-key is only enough for the tx export and tx \
+                 submit commands"
+            )
+        }
+    }
+
+    /// The signer's public key, without loading their private key if only the public key is given
+    pub fn load_public_key(&self, keystore_path: Option<&Path>) -> Result<PublicKey> {
+        match &self.public_key {
+            Some(public_key) => {
+                PublicKey::from_str(public_key).map_err(|e| anyhow!("Invalid public key: {}", e))
+            }
+            None => Ok(self.load_signer(keystore_path)?.public()),
+        }
+    }
+}
+
+/// Opponents' public keys by name, eg.
+/// ```toml
+/// alice = "AKl..."
+/// ```
+#[derive(Debug, Default)]
+pub struct AddressBook(BTreeMap<String, PublicKey>);
+
+impl AddressBook {
+    pub fn default_path() -> Option<PathBuf> {
+        app_config_dir().map(|dir| dir.join(ADDRESS_BOOK_FILE_NAME))
+    }
+
+    /// A missing file is the same as an empty address book
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Could not read address book {}", path.display()))?;
+        let entries: BTreeMap<String, String> = toml::from_str(&contents)
+            .with_context(|| format!("Could not parse address book {}", path.display()))?;
+        entries
+            .into_iter()
+            .map(|(name, pub_key)| {
+                let pub_key = PublicKey::from_str(&pub_key)
+                    .map_err(|e| anyhow!("Invalid public key of {}: {}", name, e))?;
+                Ok((name, pub_key))
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PublicKey> {
+        self.0.get(name)
+    }
+}
+
+/// Resolves the opponent, given as a raw public key in base64, a name in the address book, or the
+/// address or alias of a key in the keystore.
+pub fn resolve_opponent(
+    opponent: &str,
+    address_book: &AddressBook,
+    keystore_path: Option<&Path>,
+) -> Result<PublicKey> {
+    if let Ok(pub_key) = PublicKey::from_str(opponent) {
+        return Ok(pub_key);
+    }
+    if let Some(pub_key) = address_book.get(opponent) {
+        return Ok(pub_key.clone());
+    }
+    let keystore = open_keystore(keystore_path)?;
+    let address = keystore_address(&keystore, opponent).map_err(|_| {
+        anyhow!(
+            "Opponent {} is neither a public key, nor in the address book or the keystore",
+            opponent
+        )
+    })?;
+    Ok(keystore.get_key(&address)?.public())
+}
+
+/// `~/.sui/sui_config/sui.keystore`, as the Sui CLI uses
+pub fn default_keystore_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|dir| dir.join(".sui").join("sui_config").join("sui.keystore"))
+}
+
+fn open_keystore(path: Option<&Path>) -> Result<FileBasedKeystore> {
+    let path = path
+        .map(Path::to_path_buf)
+        .or_else(default_keystore_path)
+        .ok_or(anyhow!("Could not find the Sui keystore"))?;
+    FileBasedKeystore::new(&path)
+        .with_context(|| format!("Could not open keystore {}", path.display()))
+}
+
+/// The address of a key in the keystore, given either the address itself or its alias
+fn keystore_address(keystore: &FileBasedKeystore, address_or_alias: &str) -> Result<SuiAddress> {
+    match SuiAddress::from_str(address_or_alias) {
+        Ok(address) => Ok(address),
+        Err(_) => keystore
+            .get_address_by_alias(address_or_alias.to_string())
+            .copied(),
+    }
+}
+
+fn parse_private_key(private_key: &str) -> Result<SuiKeyPair> {
+    SuiKeyPair::from_str(private_key.trim()).map_err(|e| anyhow!(e))
+}
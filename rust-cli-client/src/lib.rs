@@ -1,6 +1,27 @@
+pub mod ai;
 pub mod consts;
 pub mod contract_structs;
+pub mod dry_run;
+pub mod error;
+pub mod gas_manager;
+pub mod game_backend;
+pub mod game_engine;
+pub mod game_loop;
+pub mod game_store;
+pub mod history;
+pub mod in_memory_backend;
+pub mod keys;
+pub mod move_abort;
+pub mod multi_sig_1_out_of_n;
 pub mod multi_sig_to_string;
+pub mod network_config;
+pub mod notation;
+pub mod offline;
+pub mod pagination;
+pub mod recovery;
+pub mod replay;
 pub mod row_col;
 pub mod startup;
 pub mod tic_tac_toe_client;
+pub mod turn_state_machine;
+pub mod turn_watcher;
@@ -1,245 +1,702 @@
+use std::fs;
 use std::io::{stdin, stdout, Write};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::thread;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
 
-use anyhow::{anyhow, Result};
-use async_recursion::async_recursion;
+use anyhow::{anyhow, Context, Result};
 use clap::Parser;
 
-use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionBlockEffectsAPI};
+use sui_sdk::rpc_types::{
+    SuiExecutionStatus, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+};
 use sui_types::base_types::{ObjectID, SuiAddress};
-use sui_types::object::Owner;
-use sui_types::storage::WriteKind;
+use sui_types::gas_coin::MIST_PER_SUI;
+use sui_types::transaction::TransactionDataAPI;
 
-use rust_cli_client::contract_structs::Mark;
-use rust_cli_client::row_col::RowCol;
-use rust_cli_client::startup::{Config, Player, TicTacToeOpts};
-use rust_cli_client::tic_tac_toe_client::{FetchGameFilter, TicTacToeClient};
+use rust_cli_client::ai::{Bot, BotLevel};
+use rust_cli_client::contract_structs::{TicTacToe, TicTacToeTrophy};
+use rust_cli_client::dry_run::DryRunMode;
+use rust_cli_client::error::TicTacToeError;
+use rust_cli_client::game_backend::GameBackend;
+use rust_cli_client::game_engine::{self, Board, GameStatus};
+use rust_cli_client::game_loop::{
+    find_game_info, play_game, print_gameboard, print_gameboard_highlighted, resume_game_info,
+    MoveInput,
+};
+use rust_cli_client::game_store::GameStore;
+use rust_cli_client::history::fetch_history;
+use rust_cli_client::in_memory_backend::InMemoryBackend;
+use rust_cli_client::network_config::NetworkConfig;
+use rust_cli_client::notation::GameNotation;
+use rust_cli_client::offline;
+use rust_cli_client::replay::{Replay, ReplayControl, REPLAY_HELP};
+use rust_cli_client::row_col::{Col, Row, RowCol};
+use rust_cli_client::startup::{
+    Command, Config, GameCommand, NotationCommand, TicTacToeOpts, TrophiesCommand, TxCommand,
+};
+use rust_cli_client::tic_tac_toe_client::{
+    FetchGameFilter, MultisigCall, TicTacToeClient, TransactionEvent,
+};
+use rust_cli_client::turn_watcher::PollConfig;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let config = Config::try_from(TicTacToeOpts::parse())?;
-    let client = TicTacToeClient::new(Some("https://rpc.testnet.sui.io:443")).await?;
-
-    // First try to fetch game
-    let game_info = match client
-        .fetch_available_game(
-            &config.multisig_pub_key,
-            Some(&FetchGameFilter::FinishedFilter(true)),
-        )
-        .await
-    {
-        Ok(game) => {
-            let possible_owner = match game.cur_turn%2 {
-                0 => SuiAddress::from(&config.multisig_pub_key.pubkeys()[0].0),
-                1 => SuiAddress::from(&config.multisig_pub_key.pubkeys()[1].0),
-                _ => panic!("Solar electromagnetic radiation"),
-            };
-            let mark: Mark = match client
-                .find_mark(
-                    game.id.id.bytes,
-                    possible_owner
-                )
-                .await
-            {
-                Ok(mark) => mark,
-                Err(e) => match e.to_string().as_str() {
-                    "No mark found" => {
-                        client
-                            .find_mark(game.id.id.bytes, SuiAddress::from(&config.multisig_pub_key))
-                            .await?
-                    }
-                    _ => return Err(e),
-                },
-            };
-            GameInfo {
-                game_id: game.id.id.bytes,
-                mark_id: mark.id.id.bytes,
-            }
+    let opts = TicTacToeOpts::parse();
+    match opts.command.clone() {
+        Command::Game(command) => {
+            let network = NetworkConfig::load(&opts.network)?;
+            game_command(opts, &network, command).await
         }
-        Err(e) => match e.to_string().as_str() {
-            "No available games" => {
-                // If game doesn't exist, create it
-                let create_game_resp = client
-                    .create_game(&config.signer, config.multisig_pub_key.clone(), None)
-                    .await?;
-                if create_game_resp.errors.len() != 0 {
-                    return Err(anyhow!(
-                        "Error creating game: {:?}",
-                        create_game_resp.errors
-                    ));
-                }
-                let status = create_game_resp
-                    .effects
-                    .as_ref()
-                    .ok_or(anyhow!("No effects"))?
-                    .status();
-                if let SuiExecutionStatus::Failure { error } = status {
-                    return Err(anyhow!("Error creating game: {:?}", error));
-                }
-
-                let Some(effects) = create_game_resp.effects else {
-                    return Err(anyhow!("No effects"));
-                };
-                // TODO: find out if the object type is also available at the response
-                dbg!(&effects);
-
-                // Get the game.id as the only object created under the multisig-account from the response
-                let game_id = effects
-                    .all_changed_objects()
-                    .iter()
-                    .filter(|(obj_ref, write_kind)| match write_kind {
-                        WriteKind::Create => {
-                            let Owner::AddressOwner(owner_addr) = obj_ref.owner else {
-                                return false;
-                            };
-                            owner_addr == SuiAddress::from(&config.multisig_pub_key)
-                        }
-                        _ => false,
-                    })
-                    .next()
-                    .ok_or(anyhow!("No object created under multisig account"))?
-                    .0
-                    .object_id();
-                let mark_id = effects
-                    .all_changed_objects()
-                    .iter()
-                    .filter(|(obj_ref, write_kind)| match write_kind {
-                        WriteKind::Create => {
-                            let Owner::AddressOwner(owner_addr) = obj_ref.owner else {
-                                return false;
-                            };
-                            owner_addr == SuiAddress::from(&config.multisig_pub_key.pubkeys()[0].0)
-                        }
-                        _ => false,
-                    })
-                    .next()
-                    .ok_or(anyhow!("No object created under first participant account"))?
-                    .0
-                    .object_id();
-                GameInfo { game_id, mark_id }
+        Command::Notation { command } => match command {
+            NotationCommand::Export { game_id, out, json } => {
+                let network = NetworkConfig::load(&opts.network)?;
+                let client = TicTacToeClient::new(&network).await?;
+                export_notation(&client, game_id, out, json).await
             }
-            _ => return Err(e),
+            // Without the network
+            NotationCommand::Import { file } => import_notation(&file).await,
         },
+        Command::Tx { command } => {
+            let network = NetworkConfig::load(&opts.network)?;
+            tx(&opts, &network, opts.dry_run_mode(), command).await
+        }
+    }
+}
+
+/// Runs a command of the signer, who plays from this machine
+async fn game_command(
+    opts: TicTacToeOpts,
+    network: &NetworkConfig,
+    command: GameCommand,
+) -> Result<()> {
+    let poll = opts.poll_config();
+    let bot = opts.bot;
+    let dry_run = opts.dry_run_mode();
+    let store = match opts.game_store_path() {
+        Some(path) => {
+            let store = GameStore::load(&path, network.network)?;
+            Some(Arc::new(Mutex::new(store)))
+        }
+        None => None,
     };
+    let config = Config::try_from(opts)?;
+    let mut client = TicTacToeClient::new(network)
+        .await?
+        .with_dry_run(dry_run)
+        .with_gas_estimate_listener(|estimate| println!("{}", estimate));
+    if let Some(store) = store.clone() {
+        let multisig_addr = config.multisig.admin_address();
+        client = client.with_transaction_listener(move |event| {
+            let mut store = store.lock().unwrap();
+            match event {
+                TransactionEvent::Submitted(digest) => store.add_pending(multisig_addr, digest),
+                TransactionEvent::Settled(digest) => store.remove_pending(multisig_addr, digest),
+            }
+            if let Err(e) = store.save() {
+                println!("Could not save game store: {}", e);
+            }
+        });
+    }
+    let store = store.as_deref();
+    if let Some(store) = store {
+        reconcile_pending(&client, &config, store).await?;
+    }
 
-    play_game(client, config, game_info).await?;
+    if dry_run == DryRunMode::Only {
+        return dry_run_command(&client, &config, command, bot).await;
+    }
+    match command {
+        GameCommand::New => {
+            let game_info =
+                GameBackend::create_game(&client, &config.signer, &config.multisig).await?;
+            if let Some(store) = store {
+                let mut store = store.lock().unwrap();
+                store.record_game(config.multisig.admin_address(), &game_info);
+                store.save()?;
+            }
+            println!("Created game {}", game_info.game_id);
+            println!("Mark: {}", game_info.mark_id);
+            Ok(())
+        }
+        GameCommand::Join { game_id } => {
+            let game = client.fetch_game(game_id).await?;
+            play(&client, &config, game, poll, store, bot).await
+        }
+        GameCommand::List => list(&client, &config, store).await,
+        GameCommand::Show { game_id } => {
+            let game = client.fetch_game(game_id).await?;
+            print_gameboard(&game.gameboard)?;
+            println!("{}", game_status(&game)?);
+            Ok(())
+        }
+        GameCommand::History { game_id } => history(&client, game_id).await,
+        GameCommand::Replay { game_id } => replay(&client, game_id).await,
+        GameCommand::Play { game_id } => {
+            let game = client.fetch_game(game_id).await?;
+            play(&client, &config, game, poll, store, bot).await
+        }
+        GameCommand::Resign { game_id } => resign(&client, &config, game_id, store).await,
+        GameCommand::Delete { game_id } => delete(&client, &config, game_id, store).await,
+        GameCommand::Cleanup => cleanup(&client, &config, store).await,
+        GameCommand::Trophies { command } => trophies(&client, &config, command).await,
+    }
+}
 
+/// Prints the effects the transaction of the command would have, without submitting it
+async fn dry_run_command(
+    client: &TicTacToeClient,
+    config: &Config,
+    command: GameCommand,
+    bot: Option<BotLevel>,
+) -> Result<()> {
+    let resp = match command {
+        GameCommand::Play { game_id } | GameCommand::Join { game_id } => {
+            dry_run_move(client, config, game_id, bot).await?
+        }
+        GameCommand::New => {
+            client
+                .create_game(&config.signer, &config.multisig, None)
+                .await?
+        }
+        GameCommand::Delete { game_id } => {
+            client
+                .delete_game(&config.signer, &config.multisig, None, game_id)
+                .await?
+        }
+        GameCommand::Trophies {
+            command: TrophiesCommand::Burn { trophy_id },
+        } => client.burn_trophy(&config.signer, trophy_id).await?,
+        _ => {
+            return Err(anyhow!(
+                "Only the new, play, join, delete and trophies burn commands can be dry run"
+            ))
+        }
+    };
+    let effects = resp.effects.ok_or(anyhow!("No effects"))?;
+    println!("{}", effects);
     Ok(())
 }
 
-#[derive(Debug, Clone)]
-struct GameInfo {
+/// Dry runs the next transaction of the move of the signer: sending their mark with the move read
+/// as when playing, or placing it if it has been sent already
+async fn dry_run_move(
+    client: &TicTacToeClient,
+    config: &Config,
     game_id: ObjectID,
-    mark_id: ObjectID,
-}
-
-#[async_recursion]
-async fn play_game(client: TicTacToeClient, config: Config, game_info: GameInfo) -> Result<()> {
-    let game = client.fetch_game(game_info.game_id).await?;
-    let mark_id = game_info.mark_id;
-
-    println!("You are playing as {}", config.current_player());
-
-    match game.finished {
-        0 => {
-            // Not finished
-            match (game.cur_turn % 2, config.current_player()) {
-                // Which turn?
-                (0, Player::X) | (1, Player::O) => {
-                    // My turn
-                    print_gameboard(&game.gameboard)?;
-                    let row = get_row_col_input(true);
-                    let col = get_row_col_input(false);
-
-                    let mark_owner = client.mark_owner(mark_id).await?;
-                    // Sometimes during the place call a coin can be equivocated, so we may need to
-                    // only call place
-                    let skip_send = mark_owner == SuiAddress::from(&config.multisig_pub_key);
-                    if !skip_send {
-                        // cur-turn changes on mark-placement, so this shouldn't be possible
-                        debug_assert!(
-                            mark_owner == SuiAddress::from(&config.signer.public()),
-                            "Inconsistency between cur_turn and mark-owner"
-                        );
-                        client
-                            .send_mark_to_game(&config.signer, mark_id, row, col)
-                            .await?;
-                    }
-                    client
-                        .place_mark(
-                            &config.signer,
-                            config.multisig_pub_key.clone(),
-                            None,
-                            game.id.id.bytes,
-                            mark_id,
-                        )
-                        .await?;
-                    let new_gameboard = client.fetch_game(game_info.game_id).await?.gameboard;
-                    print_gameboard(&new_gameboard)?;
-                }
-                _ => {
-                    // Opponent's turn
-                    println!("Waiting for opponnent...");
-                }
-            };
-            thread::sleep(Duration::from_secs(2));
-            play_game(client, config, game_info).await
-        }
-        1 => {
-            println!("X won!");
-            Ok(())
+    bot: Option<BotLevel>,
+) -> Result<SuiTransactionBlockResponse> {
+    let game = client.fetch_game(game_id).await?;
+    check_plays(config, &game)?;
+    if game.finished != 0 {
+        return Err(anyhow!("Game {} is already finished", game_id));
+    }
+    let game_info = find_game_info(client, config, &game).await?;
+    let mark_owner = client.mark_owner(game_info.mark_id).await?;
+    if mark_owner == config.multisig.admin_address() {
+        let resp = client
+            .place_mark(
+                &config.signer,
+                &config.multisig,
+                None,
+                game_id,
+                game_info.mark_id,
+            )
+            .await?;
+        return Ok(resp);
+    }
+    if mark_owner != SuiAddress::from(&config.signer.public()) {
+        return Err(anyhow!("It is not your turn in game {}", game_id));
+    }
+
+    print_gameboard(&game.gameboard)?;
+    let (row, col) = match bot {
+        Some(level) => Bot::new(level).next_move(&game.gameboard)?,
+        None => StdinInput.next_move(&game.gameboard)?,
+    };
+    let resp = client
+        .send_mark_to_game(&config.signer, None, game_info.mark_id, row, col)
+        .await?;
+    Ok(resp)
+}
+
+/// Each step of a transaction signed elsewhere is run on its own, only signing needing the private
+/// key and only exporting and submitting needing the network
+async fn tx(
+    opts: &TicTacToeOpts,
+    network: &NetworkConfig,
+    dry_run: DryRunMode,
+    command: TxCommand,
+) -> Result<()> {
+    match command {
+        TxCommand::Export { out, call } => {
+            let call = MultisigCall::from(call);
+            let pub_key = opts.load_public_key()?;
+            let multisig = opts.multisig(pub_key.clone())?;
+            let client = TicTacToeClient::new(network).await?.with_dry_run(dry_run);
+            let tx_data = client
+                .build_multisig_call(SuiAddress::from(&pub_key), &multisig, call)
+                .await?;
+            offline::write_tx_data(&out, &tx_data)?;
+            println!(
+                "Wrote transaction {} calling {} to {}",
+                tx_data.digest(),
+                call.function(),
+                out.display()
+            );
         }
-        2 => {
-            println!("O won!");
-            Ok(())
+        TxCommand::Sign { tx_file, out } => {
+            let signer = opts.load_signer()?;
+            let multisig = opts.multisig(signer.public())?;
+            let tx_data = offline::read_tx_data(&tx_file)?;
+            if tx_data.sender() != multisig.admin_address() {
+                return Err(anyhow!(
+                    "Transaction is not sent by your multisig account {}",
+                    multisig.admin_address()
+                ));
+            }
+            println!(
+                "Signing transaction {} paid by {}, with a budget of {} MIST",
+                tx_data.digest(),
+                tx_data.gas_owner(),
+                tx_data.gas_budget()
+            );
+            offline::write_signature(&out, &offline::sign_tx_data(&signer, &tx_data))?;
+            println!("Wrote signature to {}", out.display());
         }
-        3 => {
-            println!("Draw");
-            Ok(())
+        TxCommand::Submit {
+            tx_file,
+            signature_files,
+        } => {
+            let multisig = opts.multisig(opts.load_public_key()?)?;
+            let tx_data = offline::read_tx_data(&tx_file)?;
+            let signatures = signature_files
+                .iter()
+                .map(|path| offline::read_signature(path))
+                .collect::<Result<_>>()?;
+            let transaction = multisig.combine_signatures(tx_data, signatures)?;
+            let client = TicTacToeClient::new(network).await?;
+            let resp = client
+                .execute_transaction(transaction)
+                .await
+                .context("Error submitting transaction")?;
+            println!("Submitted transaction {}", resp.digest);
         }
-        _ => Err(anyhow!("Invalid game state")),
     }
+    Ok(())
 }
 
-fn get_row_col_input(is_row: bool) -> RowCol {
-    let r_c = if is_row { "row" } else { "column" };
-    print!("Enter {} number (0-2) : ", r_c);
-    let _ = stdout().flush();
-    let mut s = String::new();
-    stdin()
-        .read_line(&mut s)
-        .expect("Did not enter a correct string");
-
-    if let Ok(row_col) = RowCol::from_str(s.trim()) {
-        row_col
+/// Checks the transactions which were pending when the CLI last stopped, as they may have been
+/// executed without the CLI knowing it
+async fn reconcile_pending(
+    client: &TicTacToeClient,
+    config: &Config,
+    store: &Mutex<GameStore>,
+) -> Result<()> {
+    let multisig_addr = config.multisig.admin_address();
+    let pending = store.lock().unwrap().take_pending(multisig_addr);
+    for digest in pending {
+        match client.transaction_status(digest).await {
+            Ok(SuiExecutionStatus::Success) => {
+                println!("Pending transaction {} was executed", digest)
+            }
+            Ok(SuiExecutionStatus::Failure { error }) => {
+                println!("Pending transaction {} failed: {}", digest, error)
+            }
+            Err(TicTacToeError::TransactionNotFound(_)) => {
+                println!("Pending transaction {} was not executed", digest)
+            }
+            Err(e) => {
+                // Checked again next time
+                store.lock().unwrap().add_pending(multisig_addr, digest);
+                println!("Could not check pending transaction {}: {}", digest, e);
+            }
+        }
+    }
+    store.lock().unwrap().save()
+}
+
+async fn play(
+    client: &TicTacToeClient,
+    config: &Config,
+    game: TicTacToe,
+    poll: PollConfig,
+    store: Option<&Mutex<GameStore>>,
+    bot: Option<BotLevel>,
+) -> Result<()> {
+    check_plays(config, &game)?;
+    if game.finished != 0 {
+        print_gameboard(&game.gameboard)?;
+        println!("{}", game_status(&game)?);
+        return Ok(());
+    }
+    if store.is_some_and(|store| is_resigned(store, config, &game)) {
+        return Err(anyhow!("You resigned from game {}", game.id.id.bytes));
+    }
+    let game_info = resume_game_info(client, config, &game, store).await?;
+
+    match bot {
+        Some(level) => {
+            let mut bot = Bot::new(level);
+            play_game(client, config, game_info, &mut bot, poll, store).await
+        }
+        None => play_game(client, config, game_info, &mut StdinInput, poll, store).await,
+    }
+}
+
+fn check_plays(config: &Config, game: &TicTacToe) -> Result<()> {
+    if !config.multisig.plays(game) {
+        return Err(anyhow!(
+            "Game {} is not played between you and your opponent",
+            game.id.id.bytes
+        ));
+    }
+    Ok(())
+}
+
+fn is_resigned(store: &Mutex<GameStore>, config: &Config, game: &TicTacToe) -> bool {
+    let store = store.lock().unwrap();
+    store.is_resigned(config.multisig.admin_address(), game.id.id.bytes)
+}
+
+/// Gives up an unfinished game. The contract has no resign entry and only deletes finished games,
+/// so the game is only recorded as resigned: it stays on chain, and the opponent is not told.
+async fn resign(
+    client: &TicTacToeClient,
+    config: &Config,
+    game_id: ObjectID,
+    store: Option<&Mutex<GameStore>>,
+) -> Result<()> {
+    let store = store.ok_or(anyhow!("Resigning needs the game store, see --game-store"))?;
+    let game = client.fetch_game(game_id).await?;
+    check_plays(config, &game)?;
+    if game.finished != 0 {
+        return Err(anyhow!(
+            "Game {} is already finished, delete it instead",
+            game_id
+        ));
+    }
+    let game_info = resume_game_info(client, config, &game, Some(store)).await?;
+    let mut store = store.lock().unwrap();
+    store
+        .record_game(config.multisig.admin_address(), &game_info)
+        .resigned = true;
+    store.save()?;
+    println!(
+        "Resigned from game {}. It stays on chain, as the contract cannot end it early",
+        game_id
+    );
+    Ok(())
+}
+
+/// Forgets a deleted game
+fn forget_game(store: Option<&Mutex<GameStore>>, config: &Config, game_id: ObjectID) -> Result<()> {
+    let Some(store) = store else {
+        return Ok(());
+    };
+    let mut store = store.lock().unwrap();
+    store.remove_game(config.multisig.admin_address(), game_id);
+    store.save()
+}
+
+async fn list(
+    client: &TicTacToeClient,
+    config: &Config,
+    store: Option<&Mutex<GameStore>>,
+) -> Result<()> {
+    let games = client.fetch_games(&config.multisig, None).await?;
+    if games.is_empty() {
+        println!("No games found");
+    }
+    for game in &games {
+        let resigned = if store.is_some_and(|store| is_resigned(store, config, game)) {
+            ", resigned"
+        } else {
+            ""
+        };
+        println!("{}: {}{}", game.id.id.bytes, game_status(game)?, resigned);
+    }
+    Ok(())
+}
+
+async fn history(client: &TicTacToeClient, game_id: ObjectID) -> Result<()> {
+    let moves = fetch_history(client, game_id).await?;
+    if moves.is_empty() {
+        println!("No moves yet");
+    }
+    for entry in &moves {
+        let at = entry
+            .timestamp_ms
+            .map(|ms| format!(" at {} ms", ms))
+            .unwrap_or_default();
+        println!(
+            "Turn {}: {} at row {}, col {}, sent by {}, placed in {}{}",
+            entry.turn + 1,
+            entry.player,
+            entry.row,
+            entry.col,
+            entry.signer,
+            entry.digest,
+            at
+        );
+        if entry.is_rejected() {
+            println!("Rejected, as the cell was taken");
+        } else {
+            print_gameboard(&entry.board.gameboard)?;
+        }
+    }
+    Ok(())
+}
+
+async fn replay(client: &TicTacToeClient, game_id: ObjectID) -> Result<()> {
+    let game = client.fetch_game(game_id).await?;
+    let mut replay = Replay::new(fetch_history(client, game_id).await?);
+    if replay.is_empty() {
+        println!("No moves yet");
+        return Ok(());
+    }
+    loop {
+        match replay.current() {
+            Some(entry) => println!(
+                "Move {}/{}: {} at row {}, col {}, in {}",
+                replay.position(),
+                replay.len(),
+                entry.player,
+                entry.row,
+                entry.col,
+                entry.digest
+            ),
+            None => println!("Move 0/{}: start of the game", replay.len()),
+        }
+        let board = replay.board();
+        let winning_line = board.winning_line().map(Vec::from);
+        print_gameboard_highlighted(&board.gameboard, &winning_line.unwrap_or_default())?;
+        if replay.current().is_some_and(|entry| entry.is_rejected()) {
+            println!("Rejected, as the cell was taken");
+        } else if replay.position() == replay.len() {
+            println!("{}", game_status(&game)?);
+        }
+
+        let control = loop {
+            print!("{}: ", REPLAY_HELP);
+            let _ = stdout().flush();
+            let mut s = String::new();
+            // Quits at the end of the input
+            if stdin().read_line(&mut s)? == 0 {
+                break ReplayControl::Quit;
+            }
+            match ReplayControl::from_str(&s) {
+                Ok(control) => break control,
+                Err(e) => println!("{}", e),
+            }
+        };
+        if !replay.apply(control) {
+            return Ok(());
+        }
+    }
+}
+
+async fn export_notation(
+    client: &TicTacToeClient,
+    game_id: ObjectID,
+    out: Option<PathBuf>,
+    json: bool,
+) -> Result<()> {
+    let game = client.fetch_game(game_id).await?;
+    let multisig_addr = client.game_owner(game_id).await?;
+    let history = fetch_history(client, game_id).await?;
+    let notation = GameNotation::from_history(&game, multisig_addr, &history)?;
+    let contents = if json {
+        notation.to_json()?
     } else {
-        get_row_col_input(is_row)
-    }
-}
-
-fn print_gameboard(gameboard: &[u8]) -> Result<()> {
-    println!("     0     1     2");
-    print!("  ┌-----┬-----┬-----┐");
-    for row in 0..3 {
-        println!();
-        print!("{} ", row);
-        for col in 0..3 {
-            let mark = match gameboard[col * 3 + row] {
-                0 => " ",
-                1 => "X",
-                2 => "O",
-                _ => {
-                    return Err(anyhow!("Unexpected cell value"));
-                }
-            };
-            print!("|  {}  ", mark)
-        }
-        println!("|");
-        print!("  ├-----┼-----┼-----┤");
-    }
-    print!("\r");
-    println!("  └-----┴-----┴-----┘");
+        notation.to_string()
+    };
+    match out {
+        Some(out) => {
+            fs::write(&out, contents)
+                .with_context(|| format!("Could not write {}", out.display()))?;
+            println!("Wrote game {} to {}", game_id, out.display());
+        }
+        None => print!("{}", contents),
+    }
+    Ok(())
+}
+
+/// Shows a game through the local simulator, without touching the network. The simulator is only
+/// used as a viewer, and dropped once the game is shown.
+async fn import_notation(file: &Path) -> Result<()> {
+    let contents =
+        fs::read_to_string(file).with_context(|| format!("Could not read {}", file.display()))?;
+    let notation = GameNotation::decode(&contents)?;
+    let game_id = notation.game_id();
+    let backend = InMemoryBackend::new();
+    backend.import_game(notation.game.clone(), notation.multisig)?;
+    let game = backend.fetch_game(game_id).await?;
+
+    println!(
+        "Game {} between X {} and O {}",
+        game_id, game.x_addr, game.o_addr
+    );
+    for (i, m) in notation.moves.iter().enumerate() {
+        let rejected = if m.rejected { ", rejected" } else { "" };
+        println!("{}. row {}, col {}{}", i + 1, m.row, m.col, rejected);
+    }
+    let winning_line = Board::from(&game).winning_line().map(Vec::from);
+    print_gameboard_highlighted(&game.gameboard, &winning_line.unwrap_or_default())?;
+    println!("{}", game_status(&game)?);
+    Ok(())
+}
+
+async fn delete(
+    client: &TicTacToeClient,
+    config: &Config,
+    game_id: ObjectID,
+    store: Option<&Mutex<GameStore>>,
+) -> Result<()> {
+    let game = client.fetch_game(game_id).await?;
+    if game.finished == 0 {
+        return Err(anyhow!("Game {} is not finished yet", game_id));
+    }
+    client
+        .delete_game(&config.signer, &config.multisig, None, game_id)
+        .await
+        .context("Error deleting game")?;
+    forget_game(store, config, game_id)?;
+    println!("Deleted game {}", game_id);
     Ok(())
 }
+
+fn game_status(game: &TicTacToe) -> Result<String> {
+    Ok(match GameStatus::try_from(game.finished)? {
+        GameStatus::InProgress => format!(
+            "In progress, {} to play",
+            game_engine::player_of_turn(game.cur_turn)
+        ),
+        GameStatus::Won(player) => format!("{} won", player),
+        GameStatus::Draw => "Draw".to_string(),
+    })
+}
+
+async fn cleanup(
+    client: &TicTacToeClient,
+    config: &Config,
+    store: Option<&Mutex<GameStore>>,
+) -> Result<()> {
+    let games = client
+        .fetch_games(&config.multisig, Some(&FetchGameFilter::Finished))
+        .await?;
+    if games.is_empty() {
+        println!("No finished games to delete");
+        return Ok(());
+    }
+
+    let mut recovered: i64 = 0;
+    for game in games {
+        let game_id = game.id.id.bytes;
+        let resp = match client
+            .delete_game(&config.signer, &config.multisig, None, game_id)
+            .await
+        {
+            Ok(resp) => resp,
+            Err(e) => {
+                println!("Could not delete game {}: {}", game_id, e);
+                continue;
+            }
+        };
+        forget_game(store, config, game_id)?;
+        // Failed deletions, aborted or with errors, are returned as errors above
+        let Some(effects) = resp.effects else {
+            println!("Deleted game {}", game_id);
+            continue;
+        };
+        // Rebate minus the cost of the deletion itself
+        let net_rebate = -effects.gas_cost_summary().net_gas_usage();
+        println!("Deleted game {}, recovered {} MIST", game_id, net_rebate);
+        recovered += net_rebate;
+    }
+    println!(
+        "Recovered {} SUI in total",
+        recovered as f64 / MIST_PER_SUI as f64
+    );
+    Ok(())
+}
+
+async fn trophies(
+    client: &TicTacToeClient,
+    config: &Config,
+    command: TrophiesCommand,
+) -> Result<()> {
+    match command {
+        TrophiesCommand::List { cursor, limit } => {
+            let page = client
+                .fetch_trophies(
+                    SuiAddress::from(&config.signer.public()),
+                    cursor,
+                    Some(limit),
+                )
+                .await?;
+            if page.data.is_empty() {
+                println!("No trophies found");
+            }
+            for trophy in &page.data {
+                println!(
+                    "{} won as {} in game {}",
+                    trophy.id,
+                    played_as(trophy),
+                    trophy.game_id
+                );
+            }
+            if let (true, Some(next_cursor)) = (page.has_next_page, page.next_cursor) {
+                println!("More trophies with: trophies list --cursor {}", next_cursor);
+            }
+        }
+        TrophiesCommand::Show { trophy_id } => {
+            let trophy = client.fetch_trophy(trophy_id).await?;
+            println!("Trophy:    {}", trophy.id);
+            println!("Game:      {}", trophy.game_id);
+            println!("Winner:    {}", trophy.winner);
+            println!("Loser:     {}", trophy.loser);
+            println!("Played as: {}", played_as(&trophy));
+        }
+        TrophiesCommand::Burn { trophy_id } => {
+            client
+                .burn_trophy(&config.signer, trophy_id)
+                .await
+                .context("Error burning trophy")?;
+            println!("Burned trophy {}", trophy_id);
+        }
+    }
+    Ok(())
+}
+
+fn played_as(trophy: &TicTacToeTrophy) -> &'static str {
+    match trophy.played_as {
+        1 => "X",
+        2 => "O",
+        _ => "unknown",
+    }
+}
+
+struct StdinInput;
+
+impl MoveInput for StdinInput {
+    fn next_move(&mut self, _gameboard: &[u8]) -> Result<(Row, Col)> {
+        Ok((get_row_col_input(true)?, get_row_col_input(false)?))
+    }
+}
+
+fn get_row_col_input(is_row: bool) -> Result<RowCol> {
+    let r_c = if is_row { "row" } else { "column" };
+    loop {
+        print!("Enter {} number (0-2) : ", r_c);
+        let _ = stdout().flush();
+        let mut s = String::new();
+        if stdin().read_line(&mut s)? == 0 {
+            return Err(anyhow!("The input ended before a {} was entered", r_c));
+        }
+
+        if let Ok(row_col) = RowCol::from_str(s.trim()) {
+            return Ok(row_col);
+        }
+    }
+}
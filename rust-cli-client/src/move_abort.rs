@@ -0,0 +1,59 @@
+//! Decodes the Move aborts out of the errors of failed transactions, e.g.
+//! `MoveAbort(MoveLocation { module: ModuleId { address: 0x.., name: Identifier("multisig_tic_tac_toe") },
+//! function: 1, instruction: 9, function_name: Some("send_mark_to_game") }, 1) in command 0`
+
+use sui_types::base_types::ObjectID;
+
+use crate::consts::MODULE_NAME;
+use crate::error::AbortCode;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveAbort {
+    /// Package of the module which aborted
+    pub package: Option<ObjectID>,
+    /// Module which aborted
+    pub module: Option<String>,
+    /// Function which aborted, which can be a helper of the called function
+    pub function: Option<String>,
+    pub code: u64,
+}
+
+impl MoveAbort {
+    /// Parses the error of a `SuiExecutionStatus::Failure`, if it is a Move abort
+    pub fn parse(error: &str) -> Option<Self> {
+        let (_, location) = error.split_once("MoveAbort(")?;
+        // The code follows the location, which is the last struct of the abort
+        let (location, code) = location.rsplit_once("}, ")?;
+        let code: String = code.chars().take_while(|c| c.is_ascii_digit()).collect();
+        Some(Self {
+            package: package_after(location, "address: "),
+            module: quoted_after(location, "name: Identifier(\""),
+            function: quoted_after(location, "function_name: Some(\""),
+            code: code.parse().ok()?,
+        })
+    }
+
+    /// The abort code of the contract published as `package_id`, if the contract aborted. Modules
+    /// of other packages can have the same name.
+    pub fn contract_code(&self, package_id: ObjectID) -> Option<AbortCode> {
+        if self.package != Some(package_id) || self.module.as_deref() != Some(MODULE_NAME) {
+            return None;
+        }
+        AbortCode::try_from(self.code).ok()
+    }
+}
+
+/// Package ID after `prefix`, written in hex with or without `0x`
+fn package_after(s: &str, prefix: &str) -> Option<ObjectID> {
+    let (_, rest) = s.split_once(prefix)?;
+    let rest = rest.strip_prefix("0x").unwrap_or(rest);
+    let hex: String = rest.chars().take_while(|c| c.is_ascii_hexdigit()).collect();
+    ObjectID::from_hex_literal(&format!("0x{}", hex)).ok()
+}
+
+/// Text between `prefix` and the next quote
+fn quoted_after(s: &str, prefix: &str) -> Option<String> {
+    let (_, rest) = s.split_once(prefix)?;
+    let (quoted, _) = rest.split_once('"')?;
+    Some(quoted.to_string())
+}
@@ -0,0 +1,198 @@
+use std::fmt::Display;
+
+use anyhow::{anyhow, bail, Result};
+
+use shared_crypto::intent::{Intent, IntentMessage};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{PublicKey, Signature, SuiKeyPair, SuiSignature};
+use sui_types::multisig::{MultiSig, MultiSigPublicKey};
+use sui_types::signature::GenericSignature;
+use sui_types::transaction::{Transaction, TransactionData, TransactionDataAPI};
+
+use crate::contract_structs::TicTacToe;
+use crate::multi_sig_to_string::MultiSigToString;
+use crate::startup::Player;
+
+/// A multisig account which any one of its participants can sign for, ie. all weights and the
+/// threshold are 1.
+/// The first participant plays as X and the second as O, while the multisig account itself is the
+/// game admin.
+#[derive(Debug, Clone)]
+pub struct MultiSig1OutOfN {
+    multisig_pub_key: MultiSigPublicKey,
+    address: SuiAddress,
+}
+
+impl TryFrom<MultiSigPublicKey> for MultiSig1OutOfN {
+    type Error = anyhow::Error;
+
+    fn try_from(multisig_pub_key: MultiSigPublicKey) -> Result<Self> {
+        if *multisig_pub_key.threshold() != 1 {
+            bail!(
+                "Multisig threshold should be 1, got {}",
+                multisig_pub_key.threshold()
+            );
+        }
+        let pubkeys = multisig_pub_key.pubkeys();
+        if pubkeys.len() < 2 {
+            bail!("Incorrect number of pubkeys in multisig: {}", pubkeys.len());
+        }
+        if let Some((pub_key, weight)) = pubkeys.iter().find(|(_, weight)| *weight != 1) {
+            bail!(
+                "Weight of {} in multisig should be 1, got {}",
+                SuiAddress::from(pub_key),
+                weight
+            );
+        }
+
+        let address = SuiAddress::from(&multisig_pub_key);
+        Ok(Self {
+            multisig_pub_key,
+            address,
+        })
+    }
+}
+
+impl MultiSig1OutOfN {
+    /// The order of the keys defines the multisig address, so it should be the same for all
+    /// participants: X first, then O.
+    pub fn new(pub_keys: Vec<PublicKey>) -> Result<Self> {
+        let weights = vec![1; pub_keys.len()];
+        MultiSigPublicKey::new(pub_keys, weights, 1)?.try_into()
+    }
+
+    pub fn multisig_pub_key(&self) -> &MultiSigPublicKey {
+        &self.multisig_pub_key
+    }
+
+    pub fn pub_keys(&self) -> impl Iterator<Item = &PublicKey> {
+        self.multisig_pub_key
+            .pubkeys()
+            .iter()
+            .map(|(pub_key, _)| pub_key)
+    }
+
+    pub fn contains(&self, pub_key: &PublicKey) -> bool {
+        self.pub_keys().any(|pk| pk == pub_key)
+    }
+
+    /// Address of the multisig account, which owns the games
+    pub fn admin_address(&self) -> SuiAddress {
+        self.address
+    }
+
+    pub fn x_pub_key(&self) -> &PublicKey {
+        &self.multisig_pub_key.pubkeys()[0].0
+    }
+
+    pub fn o_pub_key(&self) -> &PublicKey {
+        &self.multisig_pub_key.pubkeys()[1].0
+    }
+
+    pub fn x_address(&self) -> SuiAddress {
+        SuiAddress::from(self.x_pub_key())
+    }
+
+    pub fn o_address(&self) -> SuiAddress {
+        SuiAddress::from(self.o_pub_key())
+    }
+
+    pub fn player_address(&self, player: Player) -> SuiAddress {
+        match player {
+            Player::X => self.x_address(),
+            Player::O => self.o_address(),
+        }
+    }
+
+    /// Whether the game is played between the participants of the multisig, in their roles
+    pub fn plays(&self, game: &TicTacToe) -> bool {
+        game.x_addr == self.x_address() && game.o_addr == self.o_address()
+    }
+
+    /// Which player a participant of the multisig plays as
+    pub fn role_of(&self, pub_key: &PublicKey) -> Option<Player> {
+        if pub_key == self.x_pub_key() {
+            Some(Player::X)
+        } else if pub_key == self.o_pub_key() {
+            Some(Player::O)
+        } else {
+            None
+        }
+    }
+
+    /// Signs a transaction sent by the multisig account and sponsored by the signer, who can be
+    /// any of its participants, whatever their key scheme.
+    /// The signature of the signer is used both as the sponsor's signature and as the single
+    /// signature the multisig needs.
+    pub fn sign_sponsored_transaction(
+        &self,
+        signer: &SuiKeyPair,
+        tx_data: TransactionData,
+    ) -> Result<Transaction> {
+        if !self.contains(&signer.public()) {
+            bail!("Signer is not part of the multisig");
+        }
+        let intent = Intent::sui_transaction();
+        let signer_sig =
+            Signature::new_secure(&IntentMessage::new(intent.clone(), &tx_data), signer);
+        let multisig_generic_sig: GenericSignature =
+            MultiSig::combine(vec![signer_sig.clone()], self.multisig_pub_key.clone())?.into();
+        let sigs = vec![
+            GenericSignature::Signature(signer_sig),
+            multisig_generic_sig,
+        ];
+        Ok(Transaction::from_generic_sig_data(tx_data, intent, sigs))
+    }
+
+    /// Combines the signatures of a transaction sent by the multisig account, made separately by
+    /// its signers. As in `sign_sponsored_transaction`, the signature of any participant is enough
+    /// for the multisig, and the sponsor's signature is needed unless the multisig pays.
+    pub fn combine_signatures(
+        &self,
+        tx_data: TransactionData,
+        signatures: Vec<Signature>,
+    ) -> Result<Transaction> {
+        if tx_data.sender() != self.address {
+            bail!(
+                "Transaction is not sent by multisig account {}",
+                self.address
+            );
+        }
+        let intent = Intent::sui_transaction();
+        let intent_msg = IntentMessage::new(intent.clone(), &tx_data);
+        let sponsor = tx_data.gas_owner();
+
+        let mut sponsor_sig = None;
+        let mut participant_sig = None;
+        for sig in signatures {
+            let pub_key = sig.to_public_key()?;
+            let author = SuiAddress::from(&pub_key);
+            sig.verify_secure(&intent_msg, author, sig.scheme())
+                .map_err(|e| anyhow!("Signature of {} is not of the transaction: {}", author, e))?;
+            if author == sponsor {
+                sponsor_sig = Some(sig.clone());
+            }
+            if self.contains(&pub_key) && participant_sig.is_none() {
+                participant_sig = Some(sig);
+            }
+        }
+
+        let participant_sig = participant_sig.ok_or(anyhow!(
+            "None of the signatures is of a participant of the multisig"
+        ))?;
+        let mut sigs: Vec<GenericSignature> =
+            vec![MultiSig::combine(vec![participant_sig], self.multisig_pub_key.clone())?.into()];
+        if sponsor != self.address {
+            let sponsor_sig =
+                sponsor_sig.ok_or(anyhow!("Missing the signature of sponsor {}", sponsor))?;
+            sigs.insert(0, GenericSignature::Signature(sponsor_sig));
+        }
+        Ok(Transaction::from_generic_sig_data(tx_data, intent, sigs))
+    }
+}
+
+impl Display for MultiSig1OutOfN {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", MultiSigToString::to_string(&self.multisig_pub_key))
+    }
+}
@@ -1,6 +1,5 @@
 use sui_types::{multisig::MultiSigPublicKey, crypto::EncodeDecodeBase64};
 
-// TODO: pub struct MultiSig1OutOf2 w/ TryFrom<MultiSigPublicKey> asserts N sigs, weights and threshold
 pub trait MultiSigToString {
     fn to_string(&self) -> String;
 }
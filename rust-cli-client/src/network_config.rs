@@ -0,0 +1,325 @@
+//! Which network the client talks to and where the contract is published on it.
+//!
+//! Settings are layered, each layer overriding the previous one:
+//! 1. Built-in defaults of the network profiles
+//! 2. The TOML config file, by default `<config_dir>/multisig-tic-tac-toe/config.toml`
+//! 3. Environment variables, also read from a `.env` file such as the one `setup/publish.sh` writes.
+//!    They are per network, so that a contract published on localnet does not override the
+//!    package ID of testnet.
+//! 4. CLI flags
+
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+use sui_types::base_types::ObjectID;
+
+const CONFIG_DIR_NAME: &str = "multisig-tic-tac-toe";
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Selects the network profile
+pub const NETWORK_ENV: &str = "TIC_TAC_TOE_NETWORK";
+/// Fullnode RPC url of the network the contract was published on, as in the `.env` written by
+/// `setup/publish.sh`. It also selects that network, unless another one is given.
+pub const RPC_URL_ENV: &str = "SUI_NETWORK";
+/// Package ID of the contract published on the network of `SUI_NETWORK`, as in the `.env` written
+/// by `setup/publish.sh`
+pub const PACKAGE_ID_ENV: &str = "PACKAGE_ADDRESS";
+
+/// Fullnode RPC url of a network, eg. `TIC_TAC_TOE_LOCALNET_RPC_URL`
+pub fn network_rpc_url_env(network: Network) -> String {
+    format!("TIC_TAC_TOE_{}_RPC_URL", network.to_string().to_uppercase())
+}
+
+/// Fullnode websocket url of a network, eg. `TIC_TAC_TOE_LOCALNET_WS_URL`
+pub fn network_ws_url_env(network: Network) -> String {
+    format!("TIC_TAC_TOE_{}_WS_URL", network.to_string().to_uppercase())
+}
+
+/// Package ID of the contract on a network, eg. `TIC_TAC_TOE_LOCALNET_PACKAGE_ID`
+pub fn network_package_id_env(network: Network) -> String {
+    format!(
+        "TIC_TAC_TOE_{}_PACKAGE_ID",
+        network.to_string().to_uppercase()
+    )
+}
+
+const TESTNET_PACKAGE_ID: ObjectID = ObjectID::new([
+    0x24, 0x6d, 0x9e, 0x49, 0x69, 0xa9, 0xfc, 0xe6, 0x84, 0xb9, 0x25, 0xd6, 0xcd, 0x01, 0xaa, 0xb7,
+    0x6c, 0xdb, 0x04, 0xb8, 0xdb, 0x1c, 0x3f, 0xda, 0xec, 0x36, 0x66, 0x44, 0x35, 0x68, 0x04, 0x57,
+]);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Network {
+    Localnet,
+    Devnet,
+    #[default]
+    Testnet,
+    Mainnet,
+    /// Needs both the rpc url and the package ID to be configured
+    Custom,
+}
+
+impl Network {
+    fn default_profile(&self) -> NetworkProfile {
+        let (rpc_url, package_id) = match self {
+            Network::Localnet => (Some("http://127.0.0.1:9000"), None),
+            Network::Devnet => (Some("https://rpc.devnet.sui.io:443"), None),
+            Network::Testnet => (Some("https://rpc.testnet.sui.io:443"), Some(TESTNET_PACKAGE_ID)),
+            Network::Mainnet => (Some("https://rpc.mainnet.sui.io:443"), None),
+            Network::Custom => (None, None),
+        };
+        NetworkProfile {
+            rpc_url: rpc_url.map(String::from),
+            ws_url: None,
+            package_id,
+        }
+    }
+
+    /// The network of a fullnode RPC url: the one of a built-in profile, localnet for a local
+    /// node, and custom otherwise
+    pub fn of_rpc_url(rpc_url: &str) -> Network {
+        let rpc_url = rpc_url.trim_end_matches('/');
+        let known = [Network::Devnet, Network::Testnet, Network::Mainnet];
+        if let Some(network) = known
+            .into_iter()
+            .find(|network| network.default_profile().rpc_url.as_deref() == Some(rpc_url))
+        {
+            return network;
+        }
+        if rpc_url.contains("://localhost") || rpc_url.contains("://127.0.0.1") {
+            return Network::Localnet;
+        }
+        Network::Custom
+    }
+}
+
+impl FromStr for Network {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        <Network as ValueEnum>::from_str(s, true).map_err(|e| anyhow!(e))
+    }
+}
+
+impl Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Network::Localnet => "localnet",
+            Network::Devnet => "devnet",
+            Network::Testnet => "testnet",
+            Network::Mainnet => "mainnet",
+            Network::Custom => "custom",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Settings of a single network. Unset fields fall back to the previous layer.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct NetworkProfile {
+    pub rpc_url: Option<String>,
+    /// Url the fullnode serves its subscriptions on, by default the rpc url as `ws://` or `wss://`
+    pub ws_url: Option<String>,
+    pub package_id: Option<ObjectID>,
+}
+
+impl NetworkProfile {
+    fn merge(&mut self, other: NetworkProfile) {
+        if other.rpc_url.is_some() {
+            self.rpc_url = other.rpc_url;
+        }
+        if other.ws_url.is_some() {
+            self.ws_url = other.ws_url;
+        }
+        if other.package_id.is_some() {
+            self.package_id = other.package_id;
+        }
+    }
+}
+
+/// Contents of the TOML config file, eg.
+/// ```toml
+/// network = "localnet"
+///
+/// [localnet]
+/// package_id = "0x..."
+/// ```
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ConfigFile {
+    pub network: Option<Network>,
+    pub localnet: Option<NetworkProfile>,
+    pub devnet: Option<NetworkProfile>,
+    pub testnet: Option<NetworkProfile>,
+    pub mainnet: Option<NetworkProfile>,
+    pub custom: Option<NetworkProfile>,
+}
+
+/// `<config_dir>/multisig-tic-tac-toe`, where the client keeps its config files
+pub fn app_config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(CONFIG_DIR_NAME))
+}
+
+/// `<data_dir>/multisig-tic-tac-toe`, where the client keeps what it records about the games
+pub fn app_data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(CONFIG_DIR_NAME))
+}
+
+impl ConfigFile {
+    pub fn default_path() -> Option<PathBuf> {
+        app_config_dir().map(|dir| dir.join(CONFIG_FILE_NAME))
+    }
+
+    /// A missing file is the same as an empty one
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Could not read config file {}", path.display()))?;
+        toml::from_str(&contents)
+            .with_context(|| format!("Could not parse config file {}", path.display()))
+    }
+
+    pub fn profile(&self, network: Network) -> Option<&NetworkProfile> {
+        match network {
+            Network::Localnet => self.localnet.as_ref(),
+            Network::Devnet => self.devnet.as_ref(),
+            Network::Testnet => self.testnet.as_ref(),
+            Network::Mainnet => self.mainnet.as_ref(),
+            Network::Custom => self.custom.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Args)]
+pub struct NetworkOpts {
+    /// Network profile to use [default: testnet]
+    #[arg(long, value_enum)]
+    pub network: Option<Network>,
+    /// Fullnode RPC url, overrides the one of the network profile
+    #[arg(long)]
+    pub rpc_url: Option<String>,
+    /// Fullnode websocket url to subscribe to the changes of games, overrides the one of the
+    /// network profile [default: the rpc url as ws:// or wss://]
+    #[arg(long)]
+    pub ws_url: Option<String>,
+    /// Package ID of the contract, overrides the one of the network profile
+    #[arg(long)]
+    pub package_id: Option<ObjectID>,
+    /// TOML config file to use instead of the default one
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    /// .env file to read environment variables from, instead of searching for one
+    #[arg(long)]
+    pub env_file: Option<PathBuf>,
+}
+
+/// The resolved settings of the network the client talks to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkConfig {
+    pub network: Network,
+    pub rpc_url: String,
+    pub ws_url: String,
+    pub package_id: ObjectID,
+}
+
+impl NetworkConfig {
+    /// Loads all the layers: config file, `.env` and environment variables, and CLI flags.
+    pub fn load(opts: &NetworkOpts) -> Result<Self> {
+        match &opts.env_file {
+            Some(path) => {
+                dotenvy::from_path(path)
+                    .with_context(|| format!("Could not read env file {}", path.display()))?;
+            }
+            // It is fine not to have a .env file
+            None => {
+                dotenvy::dotenv().ok();
+            }
+        }
+        let file = match opts.config.clone().or_else(ConfigFile::default_path) {
+            Some(path) => ConfigFile::load(&path)?,
+            None => ConfigFile::default(),
+        };
+        let env_vars = |key: &str| env::var(key).ok().filter(|value| !value.is_empty());
+        Self::resolve(&file, env_vars, opts)
+    }
+
+    /// Merges the layers, `env_var` being the lookup of environment variables.
+    pub fn resolve(
+        file: &ConfigFile,
+        env_var: impl Fn(&str) -> Option<String>,
+        opts: &NetworkOpts,
+    ) -> Result<Self> {
+        let published_rpc_url = env_var(RPC_URL_ENV);
+        let published_network = published_rpc_url.as_deref().map(Network::of_rpc_url);
+        let network = match (opts.network, env_var(NETWORK_ENV)) {
+            (Some(network), _) => network,
+            (None, Some(network)) => network
+                .parse()
+                .with_context(|| format!("Invalid {}", NETWORK_ENV))?,
+            (None, None) => published_network.or(file.network).unwrap_or_default(),
+        };
+
+        let mut profile = network.default_profile();
+        if let Some(file_profile) = file.profile(network) {
+            profile.merge(file_profile.clone());
+        }
+        // What `setup/publish.sh` wrote is only about the network it published on
+        if published_network == Some(network) {
+            profile.merge(NetworkProfile {
+                rpc_url: published_rpc_url,
+                ws_url: None,
+                package_id: parse_package_id(&env_var, PACKAGE_ID_ENV)?,
+            });
+        }
+        profile.merge(NetworkProfile {
+            rpc_url: env_var(&network_rpc_url_env(network)),
+            ws_url: env_var(&network_ws_url_env(network)),
+            package_id: parse_package_id(&env_var, &network_package_id_env(network))?,
+        });
+        profile.merge(NetworkProfile {
+            rpc_url: opts.rpc_url.clone(),
+            ws_url: opts.ws_url.clone(),
+            package_id: opts.package_id,
+        });
+
+        let rpc_url = profile
+            .rpc_url
+            .ok_or(anyhow!("No rpc url configured for {}", network))?;
+        let ws_url = profile.ws_url.unwrap_or_else(|| ws_url_of(&rpc_url));
+        Ok(Self {
+            network,
+            rpc_url,
+            ws_url,
+            package_id: profile
+                .package_id
+                .ok_or(anyhow!("No package ID configured for {}", network))?,
+        })
+    }
+}
+
+/// The rpc url as `ws://` or `wss://`, as fullnodes serve their subscriptions on the port of the rpc
+fn ws_url_of(rpc_url: &str) -> String {
+    match rpc_url.strip_prefix("http") {
+        Some(rest) => format!("ws{}", rest),
+        None => rpc_url.to_string(),
+    }
+}
+
+fn parse_package_id(
+    env_var: &impl Fn(&str) -> Option<String>,
+    key: &str,
+) -> Result<Option<ObjectID>> {
+    env_var(key)
+        .map(|id| ObjectID::from_hex_literal(&id))
+        .transpose()
+        .with_context(|| format!("Invalid {}", key))
+}
@@ -0,0 +1,219 @@
+//! Text notation of a game, to share it in bug reports and regression tests, e.g.
+//! ```text
+//! # Lines starting with # are comments
+//! game 0x1f0...
+//! multisig 0x5a3...
+//! x 0x8c2...
+//! o 0x9d4...
+//! moves 0,0 1,1 1,1? 0,1 2,2 0,2
+//! ```
+//! Moves are `row,col` as `RowCol` parses them, in the order they were placed, and `?` marks the
+//! ones the contract bounced back as their cell was taken.
+//!
+//! The JSON form holds the `TicTacToe` object the moves lead to instead of its fields.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::id::{ID, UID};
+
+use crate::contract_structs::TicTacToe;
+use crate::game_engine::{get_index, Board, PlaceOutcome};
+use crate::history::MoveEntry;
+use crate::row_col::RowCol;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotationMove {
+    pub row: u8,
+    pub col: u8,
+    /// Bounced back, as the cell was taken
+    #[serde(default)]
+    pub rejected: bool,
+}
+
+impl Display for NotationMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.row, self.col)?;
+        if self.rejected {
+            write!(f, "?")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for NotationMove {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (cell, rejected) = match s.strip_suffix('?') {
+            Some(cell) => (cell, true),
+            None => (s, false),
+        };
+        let (row, col) = cell
+            .split_once(',')
+            .ok_or(anyhow!("Invalid move {}, expected row,col", s))?;
+        Ok(NotationMove {
+            row: RowCol::from_str(row)?.into(),
+            col: RowCol::from_str(col)?.into(),
+            rejected,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameNotation {
+    pub multisig: SuiAddress,
+    /// The game after the moves
+    pub game: TicTacToe,
+    pub moves: Vec<NotationMove>,
+}
+
+impl GameNotation {
+    /// Plays the moves from an empty gameboard, failing if the contract would not have accepted
+    /// them as they are
+    pub fn new(
+        game_id: ObjectID,
+        multisig: SuiAddress,
+        x_addr: SuiAddress,
+        o_addr: SuiAddress,
+        moves: Vec<NotationMove>,
+    ) -> Result<Self> {
+        let mut board = Board::new();
+        for (i, m) in moves.iter().enumerate() {
+            let outcome = board
+                .place_mark(get_index(m.row, m.col)?)
+                .with_context(|| format!("Invalid move {} ({})", i + 1, m))?;
+            if (outcome == PlaceOutcome::Rejected) != m.rejected {
+                bail!(
+                    "Move {} ({}) is {}rejected by the contract",
+                    i + 1,
+                    m,
+                    if m.rejected { "not " } else { "" }
+                );
+            }
+        }
+        Ok(Self {
+            multisig,
+            game: TicTacToe {
+                id: UID {
+                    id: ID { bytes: game_id },
+                },
+                gameboard: board.gameboard,
+                cur_turn: board.cur_turn,
+                x_addr,
+                o_addr,
+                finished: board.finished,
+            },
+            moves,
+        })
+    }
+
+    /// The moves rebuilt by `history`, which have to lead to `game`
+    pub fn from_history(
+        game: &TicTacToe,
+        multisig: SuiAddress,
+        history: &[MoveEntry],
+    ) -> Result<Self> {
+        let moves = history
+            .iter()
+            .map(|entry| NotationMove {
+                row: entry.row,
+                col: entry.col,
+                rejected: entry.is_rejected(),
+            })
+            .collect();
+        let notation = Self::new(game.id.id.bytes, multisig, game.x_addr, game.o_addr, moves)?;
+        if notation.game != *game {
+            bail!("Moves do not lead to game {}", game.id.id.bytes);
+        }
+        Ok(notation)
+    }
+
+    pub fn game_id(&self) -> ObjectID {
+        self.game.id.id.bytes
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Checks that the moves lead to the game, as it is not derived from them
+    pub fn from_json(json: &str) -> Result<Self> {
+        let notation: GameNotation = serde_json::from_str(json)?;
+        let game = &notation.game;
+        let replayed = Self::new(
+            game.id.id.bytes,
+            notation.multisig,
+            game.x_addr,
+            game.o_addr,
+            notation.moves.clone(),
+        )?;
+        if replayed != notation {
+            bail!("Moves do not lead to game {}", game.id.id.bytes);
+        }
+        Ok(notation)
+    }
+
+    /// Either form, JSON starting with a brace
+    pub fn decode(contents: &str) -> Result<Self> {
+        if contents.trim_start().starts_with('{') {
+            Self::from_json(contents)
+        } else {
+            Self::from_str(contents)
+        }
+    }
+}
+
+impl Display for GameNotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "game {}", self.game_id())?;
+        writeln!(f, "multisig {}", self.multisig)?;
+        writeln!(f, "x {}", self.game.x_addr)?;
+        writeln!(f, "o {}", self.game.o_addr)?;
+        write!(f, "moves")?;
+        for m in &self.moves {
+            write!(f, " {}", m)?;
+        }
+        writeln!(f)
+    }
+}
+
+impl FromStr for GameNotation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (mut game_id, mut multisig, mut x_addr, mut o_addr) = (None, None, None, None);
+        let mut moves = vec![];
+        let lines = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        for line in lines {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let value = value.trim();
+            match key {
+                "game" => game_id = Some(ObjectID::from_str(value)?),
+                "multisig" => multisig = Some(SuiAddress::from_str(value)?),
+                "x" => x_addr = Some(SuiAddress::from_str(value)?),
+                "o" => o_addr = Some(SuiAddress::from_str(value)?),
+                "moves" => {
+                    for m in value.split_whitespace() {
+                        moves.push(NotationMove::from_str(m)?);
+                    }
+                }
+                _ => bail!("Unknown line: {}", line),
+            }
+        }
+        Self::new(
+            game_id.ok_or(anyhow!("Missing game line"))?,
+            multisig.ok_or(anyhow!("Missing multisig line"))?,
+            x_addr.ok_or(anyhow!("Missing x line"))?,
+            o_addr.ok_or(anyhow!("Missing o line"))?,
+            moves,
+        )
+    }
+}
@@ -0,0 +1,57 @@
+//! Transactions of the multisig account which are signed elsewhere than where they are built and
+//! submitted, e.g. on an air-gapped machine. They are passed around in files, in base64 as
+//! `sui keytool sign` takes and outputs them, so that it can sign them too.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use fastcrypto::encoding::{Base64, Encoding};
+
+use shared_crypto::intent::{Intent, IntentMessage};
+use sui_types::crypto::{EncodeDecodeBase64, Signature, SuiKeyPair};
+use sui_types::transaction::TransactionData;
+
+/// BCS bytes of the transaction, in base64
+pub fn encode_tx_data(tx_data: &TransactionData) -> Result<String> {
+    Ok(Base64::encode(bcs::to_bytes(tx_data)?))
+}
+
+pub fn decode_tx_data(encoded: &str) -> Result<TransactionData> {
+    let bytes = Base64::decode(encoded.trim()).map_err(|e| anyhow!("Invalid base64: {}", e))?;
+    bcs::from_bytes(&bytes).context("Invalid transaction data")
+}
+
+/// Signature of the transaction by one of its signers, alone
+pub fn sign_tx_data(signer: &SuiKeyPair, tx_data: &TransactionData) -> Signature {
+    Signature::new_secure(
+        &IntentMessage::new(Intent::sui_transaction(), tx_data),
+        signer,
+    )
+}
+
+pub fn decode_signature(encoded: &str) -> Result<Signature> {
+    Signature::decode_base64(encoded.trim()).map_err(|e| anyhow!("Invalid signature: {}", e))
+}
+
+pub fn write_tx_data(path: &Path, tx_data: &TransactionData) -> Result<()> {
+    fs::write(path, encode_tx_data(tx_data)?)
+        .with_context(|| format!("Could not write transaction to {}", path.display()))
+}
+
+pub fn read_tx_data(path: &Path) -> Result<TransactionData> {
+    let encoded = fs::read_to_string(path)
+        .with_context(|| format!("Could not read transaction from {}", path.display()))?;
+    decode_tx_data(&encoded).with_context(|| format!("In {}", path.display()))
+}
+
+pub fn write_signature(path: &Path, signature: &Signature) -> Result<()> {
+    fs::write(path, signature.encode_base64())
+        .with_context(|| format!("Could not write signature to {}", path.display()))
+}
+
+pub fn read_signature(path: &Path) -> Result<Signature> {
+    let encoded = fs::read_to_string(path)
+        .with_context(|| format!("Could not read signature from {}", path.display()))?;
+    decode_signature(&encoded).with_context(|| format!("In {}", path.display()))
+}
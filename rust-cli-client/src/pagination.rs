@@ -0,0 +1,75 @@
+//! Follows the cursors of paginated RPC queries, such as `get_owned_objects`, which otherwise only
+//! return their first page.
+
+use futures::future::Future;
+use futures::pin_mut;
+use futures::stream::{self, Stream, TryStreamExt};
+
+use sui_sdk::rpc_types::Page;
+
+/// Streams the items of all the pages, fetching the next page only once the previous one has been
+/// consumed. `fetch_page` is given the cursor of the page to fetch, `None` for the first one.
+pub fn paginate<T, C, E, F, Fut>(fetch_page: F) -> impl Stream<Item = Result<T, E>>
+where
+    F: FnMut(Option<C>) -> Fut,
+    Fut: Future<Output = Result<Page<T, C>, E>>,
+{
+    paginate_from(None, fetch_page)
+}
+
+/// Same as `paginate`, for the items after `start`, the cursor of an earlier page
+pub fn paginate_from<T, C, E, F, Fut>(
+    start: Option<C>,
+    fetch_page: F,
+) -> impl Stream<Item = Result<T, E>>
+where
+    F: FnMut(Option<C>) -> Fut,
+    Fut: Future<Output = Result<Page<T, C>, E>>,
+{
+    // `None` once there are no more pages
+    let first_cursor: Option<Option<C>> = Some(start);
+    stream::try_unfold(
+        (fetch_page, first_cursor),
+        |(mut fetch_page, cursor)| async move {
+            let Some(cursor) = cursor else {
+                return Ok(None);
+            };
+            let page = fetch_page(cursor).await?;
+            let next_cursor = match page.next_cursor {
+                Some(next_cursor) if page.has_next_page => Some(Some(next_cursor)),
+                _ => None,
+            };
+            let items = stream::iter(page.data.into_iter().map(Ok));
+            Ok(Some((items, (fetch_page, next_cursor))))
+        },
+    )
+    .try_flatten()
+}
+
+/// Collects a page of at most `limit` items out of a stream, whatever the size of the pages it was
+/// fetched in. The cursor of the page is the one of its last item, given by `cursor_of`.
+pub async fn take_page<T, C, E, S>(
+    items: S,
+    limit: Option<usize>,
+    cursor_of: impl Fn(&T) -> C,
+) -> Result<Page<T, C>, E>
+where
+    S: Stream<Item = Result<T, E>>,
+{
+    pin_mut!(items);
+    let limit = limit.unwrap_or(usize::MAX);
+    let mut data = vec![];
+    while data.len() < limit {
+        match items.try_next().await? {
+            Some(item) => data.push(item),
+            None => break,
+        }
+    }
+    // Only a full page can have a next one
+    let has_next_page = data.len() == limit && items.try_next().await?.is_some();
+    Ok(Page {
+        next_cursor: data.last().map(cursor_of),
+        data,
+        has_next_page,
+    })
+}
@@ -0,0 +1,145 @@
+//! Recovery from failed transactions.
+//!
+//! Both players sponsor the multisig transactions from their own gas, so the same owned objects
+//! can end up in conflicting transactions. Failures are classified, and the recoverable ones are
+//! retried with fresh object references and backoff. Objects locked by conflicting transactions
+//! (equivocated) stay locked until the end of the epoch: a locked gas coin can be swapped for
+//! another one, but a locked game cannot be played until then.
+
+use std::cmp::min;
+use std::future::Future;
+use std::time::Duration;
+
+use futures::future;
+
+use sui_types::base_types::ObjectID;
+
+use crate::error::TicTacToeError;
+use crate::tic_tac_toe_client::Result;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    /// The objects are locked by a conflicting transaction
+    ObjectLocked(Vec<ObjectID>),
+    StaleVersion,
+    InsufficientGas,
+    Other,
+}
+
+impl Failure {
+    pub fn classify(error: &TicTacToeError) -> Self {
+        match error {
+            TicTacToeError::ObjectsLocked { objects, .. } => Failure::ObjectLocked(objects.clone()),
+            TicTacToeError::StaleObjects(_) => Failure::StaleVersion,
+            TicTacToeError::InsufficientGas(_)
+            | TicTacToeError::NoGasCoin { .. }
+            | TicTacToeError::GasBudgetTooLow { .. } => Failure::InsufficientGas,
+            _ => Failure::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(4),
+        }
+    }
+}
+
+/// Runs a transaction until it succeeds or cannot be recovered.
+///
+/// `attempt` is given the gas coins found locked so far, which it should not use. `game_objects`
+/// are the objects of the game the transaction uses, which cannot be swapped for others.
+pub async fn execute_with_recovery<T, F, Fut>(
+    policy: &RetryPolicy,
+    game_objects: &[ObjectID],
+    attempt: F,
+) -> Result<T>
+where
+    F: FnMut(Vec<ObjectID>) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    execute_once_with_recovery(policy, game_objects, || future::ready(Ok(None)), attempt).await
+}
+
+/// Same as `execute_with_recovery`, for transactions which should not land twice, such as the
+/// creation of a game. Before every retry, `landed` looks for the result of a failed attempt which
+/// landed anyway, which is returned instead of running the transaction again.
+pub async fn execute_once_with_recovery<T, L, LFut, F, Fut>(
+    policy: &RetryPolicy,
+    game_objects: &[ObjectID],
+    mut landed: L,
+    mut attempt: F,
+) -> Result<T>
+where
+    L: FnMut() -> LFut,
+    LFut: Future<Output = Result<Option<T>>>,
+    F: FnMut(Vec<ObjectID>) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut locked_gas = vec![];
+    let mut backoff = policy.initial_backoff;
+    let mut attempts = 0;
+    loop {
+        if attempts > 0 {
+            if let Some(res) = landed().await? {
+                return Ok(res);
+            }
+        }
+        let error = match attempt(locked_gas.clone()).await {
+            Ok(res) => return Ok(res),
+            Err(e) => e,
+        };
+        attempts += 1;
+
+        let failure = Failure::classify(&error);
+        match &failure {
+            Failure::ObjectLocked(objects) => {
+                let locked_game_objects: Vec<ObjectID> = objects
+                    .iter()
+                    .filter(|id| game_objects.contains(id))
+                    .copied()
+                    .collect();
+                if !locked_game_objects.is_empty() {
+                    return Err(TicTacToeError::GameLocked {
+                        objects: locked_game_objects,
+                        epoch_end: None,
+                    });
+                }
+                // Only gas coins are locked, so another one can be used
+                for id in objects {
+                    if !locked_gas.contains(id) {
+                        locked_gas.push(*id);
+                    }
+                }
+            }
+            Failure::StaleVersion => {
+                // Object references are fetched again on every attempt
+            }
+            Failure::InsufficientGas | Failure::Other => return Err(error),
+        }
+
+        if attempts >= policy.max_attempts {
+            // The game objects are not locked, or it would have failed right away
+            if let Failure::ObjectLocked(_) = failure {
+                return Err(TicTacToeError::GasLocked(locked_gas));
+            }
+            return Err(TicTacToeError::RetriesExhausted {
+                attempts,
+                source: Box::new(error),
+            });
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = min(backoff * 2, policy.max_backoff);
+    }
+}
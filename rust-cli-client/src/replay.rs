@@ -0,0 +1,91 @@
+//! Steps through the moves of a game, as rebuilt from its transactions by `history`.
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+
+use crate::game_engine::Board;
+use crate::history::MoveEntry;
+
+pub const REPLAY_HELP: &str = "[Enter] next, b back, <N> jump to move N, f first, l last, q quit";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayControl {
+    Next,
+    Back,
+    /// To the gameboard after the move of that number, 0 being the empty gameboard
+    Jump(usize),
+    First,
+    Last,
+    Quit,
+}
+
+impl FromStr for ReplayControl {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "" | "n" | "next" => Ok(ReplayControl::Next),
+            "b" | "back" => Ok(ReplayControl::Back),
+            "f" | "first" => Ok(ReplayControl::First),
+            "l" | "last" => Ok(ReplayControl::Last),
+            "q" | "quit" => Ok(ReplayControl::Quit),
+            s => s
+                .parse()
+                .map(ReplayControl::Jump)
+                .map_err(|_| anyhow!("Invalid replay control: {}", s)),
+        }
+    }
+}
+
+/// Position in the moves of a game, from 0 before the first move to the number of moves after the
+/// last one
+#[derive(Debug, Clone)]
+pub struct Replay {
+    moves: Vec<MoveEntry>,
+    position: usize,
+}
+
+impl Replay {
+    /// Starts before the first move
+    pub fn new(moves: Vec<MoveEntry>) -> Self {
+        Self { moves, position: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Move which produced the current gameboard, `None` before the first move
+    pub fn current(&self) -> Option<&MoveEntry> {
+        self.position.checked_sub(1).map(|index| &self.moves[index])
+    }
+
+    pub fn board(&self) -> Board {
+        self.current()
+            .map(|entry| entry.board.clone())
+            .unwrap_or_default()
+    }
+
+    /// Moves to another position, staying within the moves. `false` once the replay is quit.
+    pub fn apply(&mut self, control: ReplayControl) -> bool {
+        self.position = match control {
+            ReplayControl::Next => self.position + 1,
+            ReplayControl::Back => self.position.saturating_sub(1),
+            ReplayControl::Jump(position) => position,
+            ReplayControl::First => 0,
+            ReplayControl::Last => self.len(),
+            ReplayControl::Quit => return false,
+        }
+        .min(self.len());
+        true
+    }
+}
@@ -3,7 +3,7 @@ use std::str::FromStr;
 
 use anyhow::{Result, anyhow};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RowCol {
     First,
     Second,
@@ -1,53 +1,46 @@
 use std::fmt::Display;
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
-use anyhow::{Result, anyhow};
-use clap::Parser;
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
 
+use sui_types::base_types::ObjectID;
 use sui_types::crypto::{PublicKey, SuiKeyPair};
-use sui_types::multisig::MultiSigPublicKey;
 
+use crate::ai::BotLevel;
+use crate::dry_run::{DryRunMode, DEFAULT_GAS_MARGIN_PERCENT};
+use crate::game_store::GameStore;
+use crate::keys::{resolve_opponent, AddressBook, SignerOpts};
+use crate::multi_sig_1_out_of_n::MultiSig1OutOfN;
+use crate::network_config::NetworkOpts;
+use crate::tic_tac_toe_client::MultisigCall;
+use crate::turn_watcher::PollConfig;
 
 #[derive(Debug)]
 pub struct Config {
     pub signer: SuiKeyPair,
-    pub multisig_pub_key: MultiSigPublicKey,
+    pub multisig: MultiSig1OutOfN,
 }
 
 impl TryFrom<TicTacToeOpts> for Config {
     type Error = anyhow::Error;
 
     fn try_from(opts: TicTacToeOpts) -> Result<Self> {
-        let TicTacToeOpts {
-            private_key,
-            opponent_public_key,
-            playing_as,
-        } = opts;
-
-        let signer = SuiKeyPair::from_str(&private_key).map_err(|e| anyhow!(e))?;
-        let opponent_public_key =
-            PublicKey::from_str(&opponent_public_key).map_err(|e| anyhow!(e))?;
-        let pub_keys = match playing_as {
-            Player::X => vec![signer.public(), opponent_public_key],
-            Player::O => vec![opponent_public_key, signer.public()],
-        };
-
-        let multisig_pub_key = MultiSigPublicKey::new(pub_keys, vec![1, 1], 1)?;
+        let signer = opts.load_signer()?;
+        let multisig = opts.multisig(signer.public())?;
 
-        Ok(Config {
-            signer,
-            multisig_pub_key,
-        })
+        Ok(Config { signer, multisig })
     }
 }
 
 impl Config {
-    pub fn current_player(&self) -> Player {
-        if self.signer.public() == self.multisig_pub_key.pubkeys()[0].0 {
-            Player::X
-        } else {
-            Player::O
-        }
+    /// Fails if the signer is not a participant of the multisig
+    pub fn current_player(&self) -> Result<Player> {
+        self.multisig
+            .role_of(&self.signer.public())
+            .ok_or(anyhow!("Signer is not part of the multisig"))
     }
 }
 
@@ -58,18 +51,239 @@ impl Config {
     rename_all = "kebab-case"
 )]
 pub struct TicTacToeOpts {
-    /// Your private key in base64
-    #[arg(short, long, required = true)]
-    private_key: String,
-    /// Opponent's public key in base64
+    #[command(flatten)]
+    signer: SignerOpts,
+    /// Sui keystore to read keys from [default: ~/.sui/sui_config/sui.keystore]
+    #[arg(long)]
+    keystore: Option<PathBuf>,
+    /// Opponent's public key in base64, their name in the address book, or the address or alias of
+    /// their key in the keystore
     #[arg(short, long, required = true)]
-    opponent_public_key: String,
+    opponent: String,
+    /// Address book of opponents' public keys
+    /// [default: <config_dir>/multisig-tic-tac-toe/address_book.toml]
+    #[arg(long)]
+    address_book: Option<PathBuf>,
     /// X | O
     #[arg(required = true)]
     playing_as: Player,
+    #[command(flatten)]
+    pub network: NetworkOpts,
+    /// Interval of polling for the opponent's moves in ms, right after a move, when the fullnode
+    /// cannot be subscribed to
+    #[arg(long, default_value_t = 500)]
+    poll_interval_ms: u64,
+    /// Max interval of polling for the opponent's moves in ms, as it backs off while waiting
+    #[arg(long, default_value_t = 8000)]
+    max_poll_interval_ms: u64,
+    /// Dry runs the transaction of the command and prints its effects, without submitting it
+    #[arg(long, conflicts_with = "estimate_gas")]
+    dry_run: bool,
+    /// Dry runs every transaction first, to pay it with a budget estimated from its cost
+    #[arg(long)]
+    estimate_gas: bool,
+    /// Margin added to the estimated cost of a transaction for its budget, in percent
+    #[arg(long, default_value_t = DEFAULT_GAS_MARGIN_PERCENT)]
+    gas_margin_percent: u64,
+    /// Plays your moves in `play` and `join` with a bot instead of asking for them: random |
+    /// greedy | depth:<PLIES> | blunder[:<PERCENT>] | perfect
+    #[arg(long)]
+    pub bot: Option<BotLevel>,
+    /// Local record of your games and pending transactions, to resume them after a restart
+    /// [default: <data_dir>/multisig-tic-tac-toe/games.json]
+    #[arg(long)]
+    game_store: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+impl TicTacToeOpts {
+    pub fn load_signer(&self) -> Result<SuiKeyPair> {
+        self.signer.load_signer(self.keystore.as_deref())
+    }
+
+    /// Your public key, without loading your private key if only `--public-key` is given
+    pub fn load_public_key(&self) -> Result<PublicKey> {
+        self.signer.load_public_key(self.keystore.as_deref())
+    }
+
+    /// The multisig account between you, of public key `own_pub_key`, and the opponent
+    pub fn multisig(&self, own_pub_key: PublicKey) -> Result<MultiSig1OutOfN> {
+        let keystore = self.keystore.as_deref();
+        let address_book = match self.address_book.clone().or_else(AddressBook::default_path) {
+            Some(path) => AddressBook::load(&path)?,
+            None => AddressBook::default(),
+        };
+        let opponent_public_key = resolve_opponent(&self.opponent, &address_book, keystore)?;
+        let pub_keys = match self.playing_as {
+            Player::X => vec![own_pub_key, opponent_public_key],
+            Player::O => vec![opponent_public_key, own_pub_key],
+        };
+
+        MultiSig1OutOfN::new(pub_keys)
+    }
+
+    pub fn poll_config(&self) -> PollConfig {
+        PollConfig {
+            initial_interval: Duration::from_millis(self.poll_interval_ms),
+            max_interval: Duration::from_millis(self.max_poll_interval_ms),
+            ..PollConfig::default()
+        }
+    }
+
+    /// `None` if there is no data directory to keep the store in
+    pub fn game_store_path(&self) -> Option<PathBuf> {
+        self.game_store.clone().or_else(GameStore::default_path)
+    }
+
+    pub fn dry_run_mode(&self) -> DryRunMode {
+        if self.dry_run {
+            DryRunMode::Only
+        } else if self.estimate_gas {
+            DryRunMode::Estimate {
+                margin_percent: self.gas_margin_percent,
+            }
+        } else {
+            DryRunMode::Off
+        }
+    }
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    #[command(flatten)]
+    Game(GameCommand),
+    /// Games in a text notation, to share them
+    Notation {
+        #[command(subcommand)]
+        command: NotationCommand,
+    },
+    /// Transactions of the multisig account signed with a key held elsewhere, e.g. on an
+    /// air-gapped machine
+    Tx {
+        #[command(subcommand)]
+        command: TxCommand,
+    },
+}
+
+/// Commands run with the keys of the signer and the game store
+#[derive(Debug, Clone, Subcommand)]
+pub enum GameCommand {
+    /// Creates a new game owned by the multisig account
+    New,
+    /// Joins a game created by the opponent and plays it
+    Join { game_id: ObjectID },
+    /// Lists all the games of the multisig account
+    List,
+    /// Shows the gameboard and the status of a game
+    Show { game_id: ObjectID },
+    /// Shows the moves of a game, rebuilt from its transactions
+    History { game_id: ObjectID },
+    /// Steps through the moves of a game
+    Replay { game_id: ObjectID },
+    /// Plays a game until it is finished
+    Play { game_id: ObjectID },
+    /// Gives up an unfinished game. The contract cannot end a game early, so the game is only
+    /// recorded as resigned in the game store, and `play` and `join` refuse it from then on.
+    Resign { game_id: ObjectID },
+    /// Deletes a finished game
+    Delete { game_id: ObjectID },
+    /// Deletes all the finished games of the multisig account, to reclaim their storage rebates
+    Cleanup,
+    /// Trophies won by you
+    Trophies {
+        #[command(subcommand)]
+        command: TrophiesCommand,
+    },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum TrophiesCommand {
+    /// Lists a page of your trophies
+    List {
+        /// Trophy id after which the page starts
+        #[arg(long)]
+        cursor: Option<ObjectID>,
+        /// Max number of trophies in the page
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Shows the details of a trophy
+    Show { trophy_id: ObjectID },
+    /// Deletes a trophy
+    Burn { trophy_id: ObjectID },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum NotationCommand {
+    /// Writes the notation of a game, rebuilt from its transactions
+    Export {
+        game_id: ObjectID,
+        /// File to write the notation to [default: stdout]
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Writes the JSON form instead
+        #[arg(long)]
+        json: bool,
+    },
+    /// Shows a game read from its notation, in either form. The local simulator it is loaded into
+    /// is only a viewer: the game cannot be played on from there, and nothing is kept.
+    Import { file: PathBuf },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum TxCommand {
+    /// Builds a transaction of the multisig account paid by you, and writes it in base64 to a file
+    Export {
+        /// File to write the transaction to
+        #[arg(long)]
+        out: PathBuf,
+        #[command(subcommand)]
+        call: CallCommand,
+    },
+    /// Signs a transaction read from a file, and writes the signature in base64 to a file
+    Sign {
+        tx_file: PathBuf,
+        /// File to write the signature to
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Combines the signatures of a transaction into the multisig one, and submits it
+    Submit {
+        tx_file: PathBuf,
+        /// Files of the signatures, of a participant of the multisig and of the sponsor
+        #[arg(required = true)]
+        signature_files: Vec<PathBuf>,
+    },
+}
+
+/// Functions of the contract called by the multisig account
+#[derive(Debug, Clone, Copy, Subcommand)]
+pub enum CallCommand {
+    /// Creates a new game, passing its mark to player X
+    CreateGame,
+    /// Places the mark sent to the game
+    PlaceMark {
+        game_id: ObjectID,
+        mark_id: ObjectID,
+    },
+    /// Deletes a finished game
+    DeleteGame { game_id: ObjectID },
+}
+
+impl From<CallCommand> for MultisigCall {
+    fn from(command: CallCommand) -> Self {
+        match command {
+            CallCommand::CreateGame => MultisigCall::CreateGame,
+            CallCommand::PlaceMark { game_id, mark_id } => {
+                MultisigCall::PlaceMark { game_id, mark_id }
+            }
+            CallCommand::DeleteGame { game_id } => MultisigCall::DeleteGame { game_id },
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Player {
     X,
     O,
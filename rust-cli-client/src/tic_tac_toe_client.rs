@@ -1,289 +1,578 @@
+use std::ops::ControlFlow;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use anyhow::{anyhow, Result};
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use futures::{future, pin_mut};
+use tokio::sync::watch;
 
-use move_core_types::language_storage::StructTag;
 use shared_crypto::intent::{Intent, IntentMessage};
-use sui_sdk::apis::ReadApi;
 use sui_sdk::json::SuiJsonValue;
 use sui_sdk::rpc_types::{
-    SuiData, SuiMoveStruct, SuiObjectData, SuiObjectDataFilter, SuiObjectDataOptions,
-    SuiObjectResponseQuery, SuiParsedData, SuiParsedMoveObject, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions,
+    Page, SuiArgument, SuiCallArg, SuiCommand, SuiData, SuiExecutionStatus, SuiObjectArg,
+    SuiObjectData, SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse,
+    SuiObjectResponseQuery, SuiTransactionBlockDataAPI, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockKind, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    SuiTransactionBlockResponseQuery, TransactionFilter,
 };
 use sui_sdk::{SuiClient, SuiClientBuilder};
-use sui_transaction_builder::DataReader;
-use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
-use sui_types::crypto::{EncodeDecodeBase64, Signature, SuiKeyPair};
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest};
+use sui_types::crypto::{Signature, SuiKeyPair};
 use sui_types::gas_coin::GasCoin;
-use sui_types::multisig::{MultiSig, MultiSigPublicKey};
 use sui_types::object::Owner;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
-use sui_types::signature::GenericSignature;
-use sui_types::transaction::{Transaction, TransactionData};
-use sui_types::Identifier;
+use sui_types::transaction::{Transaction, TransactionData, TransactionDataAPI};
 
-use crate::consts::{
-    MARK_STRUCT_NAME, MODULE_NAME, PACKAGE_ID, SUI_FULLNODE_URL, TIC_TAC_TOE_STRUCT_NAME,
-    TX_GAS_BUDGET,
-};
-use crate::contract_structs::{Mark, TicTacToe};
-use crate::multi_sig_to_string::MultiSigToString;
+use crate::consts::{MODULE_NAME, TX_GAS_BUDGET};
+use crate::contract_structs::{decode_move_struct, Mark, MoveStruct, TicTacToe, TicTacToeTrophy};
+use crate::dry_run::{DryRunMode, GasEstimate};
+use crate::error::TicTacToeError;
+use crate::gas_manager::{GasCoinInfo, GasKey, GasManager, GasPlan, GasReservation, MergePlan};
+use crate::history::{MarkCall, MarkTransaction};
+use crate::multi_sig_1_out_of_n::MultiSig1OutOfN;
+use crate::network_config::NetworkConfig;
+use crate::pagination::{paginate, paginate_from, take_page};
+use crate::recovery::RetryPolicy;
 use crate::row_col::{Col, Row};
 
-pub struct TicTacToeClient(SuiClient);
+pub type TrophyPage = Page<TicTacToeTrophy, ObjectID>;
+
+pub type Result<T, E = TicTacToeError> = std::result::Result<T, E>;
+
+pub type GasEstimateListener = Arc<dyn Fn(&GasEstimate) + Send + Sync>;
+
+pub type TransactionListener = Arc<dyn Fn(TransactionEvent) + Send + Sync>;
+
+/// Lifecycle of a transaction submitted by the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionEvent {
+    /// About to be submitted, with an outcome unknown until it settles
+    Submitted(TransactionDigest),
+    /// Known to be executed, successfully or not, or rejected
+    Settled(TransactionDigest),
+}
+
+/// Plans of gas payment tried before giving up, as concurrent transactions can reserve the coins
+const MAX_GAS_PLANS: usize = 3;
+
+/// Error of the nodes which fail on the transactions they have not executed, instead of leaving
+/// them out. Only their message tells it apart from the other RPC errors.
+const TRANSACTION_NOT_FOUND: &str = "Could not find the referenced transaction";
+
+pub struct TicTacToeClient {
+    client: SuiClient,
+    rpc_url: String,
+    ws_url: String,
+    package_id: ObjectID,
+    retry_policy: RetryPolicy,
+    gas_manager: GasManager,
+    dry_run: DryRunMode,
+    gas_estimate_listener: Option<GasEstimateListener>,
+    transaction_listener: Option<TransactionListener>,
+}
 
 impl TicTacToeClient {
     // =========================== Constructor ============================
-    pub async fn new(url: Option<&str>) -> Result<Self> {
-        let url = url.unwrap_or(SUI_FULLNODE_URL);
-        let client = SuiClientBuilder::default().build(url).await?;
-        Ok(Self(client))
+    pub async fn new(network: &NetworkConfig) -> Result<Self> {
+        let client = SuiClientBuilder::default().build(&network.rpc_url).await?;
+        Ok(Self {
+            client,
+            rpc_url: network.rpc_url.clone(),
+            ws_url: network.ws_url.clone(),
+            package_id: network.package_id,
+            retry_policy: RetryPolicy::default(),
+            gas_manager: GasManager::new(),
+            dry_run: DryRunMode::Off,
+            gas_estimate_listener: None,
+            transaction_listener: None,
+        })
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Dry runs the contract calls before submitting them, or instead of submitting them
+    pub fn with_dry_run(mut self, dry_run: DryRunMode) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Called with the gas estimate of every dry run contract call
+    pub fn with_gas_estimate_listener(
+        mut self,
+        listener: impl Fn(&GasEstimate) + Send + Sync + 'static,
+    ) -> Self {
+        self.gas_estimate_listener = Some(Arc::new(listener));
+        self
+    }
+
+    /// Called before every transaction is submitted and once its outcome is known, so that
+    /// transactions in flight can be reconciled after a restart
+    pub fn with_transaction_listener(
+        mut self,
+        listener: impl Fn(TransactionEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.transaction_listener = Some(Arc::new(listener));
+        self
     }
 
     // ============================= Util fns =============================
 
-    // Copied from TransactionBuilder in sui-transaction-builder.
-    // Selects gas object to use
-    pub async fn select_gas(
+    /// Reserves a gas coin of the signer for a transaction of `budget`, merging dust coins and
+    /// splitting a coin dedicated to the game of `game_object` when needed. The coin is reserved
+    /// until the reservation is dropped, so that no concurrent transaction pays with it.
+    pub async fn reserve_gas(
         &self,
-        signer_addr: SuiAddress,
-        input_gas: Option<ObjectID>,
+        signer: &SuiKeyPair,
+        game_object: Option<ObjectID>,
         budget: u64,
-        exclude_objects: Vec<ObjectID>,
-        // gas_price: u64,
-    ) -> Result<ObjectRef> {
-        // if budget < gas_price {
-        //     bail!("Gas budget {budget} is less than the reference gas price {gas_price}. The gas budget must be at least the current reference gas price of {gas_price}.")
-        // }
-        if let Some(gas) = input_gas {
-            let read_api = self.0.read_api();
-            read_api
-                .get_object_with_options(gas, SuiObjectDataOptions::new())
-                .await?
-                .object_ref_if_exists()
-                .ok_or(anyhow!("No object-ref"))
+        excluded: Vec<ObjectID>,
+    ) -> Result<GasReservation<'_>> {
+        let gas_price = self.client.read_api().get_reference_gas_price().await?;
+        if budget < gas_price {
+            return Err(TicTacToeError::GasBudgetTooLow { budget, gas_price });
+        }
+        let owner = SuiAddress::from(&signer.public());
+        let key = game_object.map(|game_object| GasKey { owner, game_object });
+
+        // Dry runs only must not change the coins, which merges and splits are executed to do
+        let dry_run_only = self.dry_run == DryRunMode::Only;
+        let mut coins = self.gas_coins(owner).await?;
+        let merge = if dry_run_only {
+            None
         } else {
-            let read_api = self.0.read_api();
-            let gas_objs =
-                <ReadApi as DataReader>::get_owned_objects(read_api, signer_addr, GasCoin::type_())
-                    .await?; // why not reference instead of move for signer?
-
-            for obj in gas_objs {
-                let response = read_api
-                    .get_object_with_options(obj.object_id, SuiObjectDataOptions::new().with_bcs())
-                    .await?;
-                let obj = response.object()?;
-                let gas: GasCoin = bcs::from_bytes(
-                    &obj.bcs
-                        .as_ref()
-                        .ok_or_else(|| anyhow!("bcs field is unexpectedly empty"))?
-                        .try_as_move()
-                        .ok_or_else(|| anyhow!("Cannot parse move object to gas object"))?
-                        .bcs_bytes,
-                )?;
-                if !exclude_objects.contains(&obj.object_id) && gas.value() >= budget {
-                    return Ok(obj.object_ref());
+            self.gas_manager.merge_plan(&coins, budget, &excluded)
+        };
+        if let Some(merge) = merge {
+            // Merging only saves coins, so the transaction is paid with the coins as they are if it
+            // fails
+            match self.merge_gas_coins(signer, merge, gas_price, budget).await {
+                Ok(()) => coins = self.gas_coins(owner).await?,
+                Err(e) => println!("Could not merge the dust gas coins: {}", e),
+            }
+        }
+
+        // Other transactions may reserve the planned coin in the meantime
+        for _ in 0..MAX_GAS_PLANS {
+            match self.gas_manager.plan(key, &coins, budget, &excluded) {
+                Some(GasPlan::Use(object_ref)) => {
+                    if let Some(reservation) = self.gas_manager.reserve(object_ref, key) {
+                        return Ok(reservation);
+                    }
                 }
+                // The dry run is paid with the coin as it is
+                Some(GasPlan::Split { from, .. }) if dry_run_only => {
+                    if let Some(reservation) = self.gas_manager.reserve(from, None) {
+                        return Ok(reservation);
+                    }
+                }
+                Some(GasPlan::Split { from, amount }) => {
+                    if let Some(reservation) = self.gas_manager.reserve(from, None) {
+                        // Splitting only dedicates a coin to the game, so the transaction is paid
+                        // with the coin unsplit if it fails
+                        let split = self
+                            .split_gas_coin(signer, &reservation, amount, gas_price, budget)
+                            .await;
+                        if let Err(e) = split {
+                            println!("Could not split a gas coin for the game: {}", e);
+                            // A failed split which was executed anyway changed the coin
+                            drop(reservation);
+                            coins = self.gas_coins(owner).await?;
+                            if let Some(coin) = coins.iter().find(|coin| coin.id() == from.0) {
+                                if let Some(reservation) =
+                                    self.gas_manager.reserve(coin.object_ref, None)
+                                {
+                                    return Ok(reservation);
+                                }
+                            }
+                            continue;
+                        }
+                    }
+                    // The split coin is the smallest one to pay for the game from now on
+                    coins = self.gas_coins(owner).await?;
+                }
+                None => break,
             }
-            Err(anyhow!("Cannot find gas coin for signer address [{signer_addr}] with amount sufficient for the required gas amount [{budget}]."))
         }
+        Err(TicTacToeError::NoGasCoin {
+            address: owner,
+            budget,
+        })
     }
 
-    // ========================== Contract calls ==========================
+    /// The gas coins of `owner`
+    async fn gas_coins(&self, owner: SuiAddress) -> Result<Vec<GasCoinInfo>> {
+        let query = SuiObjectResponseQuery::new(
+            Some(SuiObjectDataFilter::StructType(GasCoin::type_())),
+            Some(SuiObjectDataOptions::new().with_bcs()),
+        );
+        let gas_objs = self.owned_objects(owner, query, None);
+        pin_mut!(gas_objs);
 
-    pub async fn create_game(
+        let mut coins = vec![];
+        while let Some(response) = gas_objs.try_next().await? {
+            let obj = response.object().map_err(TicTacToeError::ObjectResponse)?;
+            let gas: GasCoin = self.decode(obj)?;
+            coins.push(GasCoinInfo {
+                object_ref: obj.object_ref(),
+                balance: gas.value(),
+            });
+        }
+        Ok(coins)
+    }
+
+    /// Merges the dust coins into the primary one, by paying for a transaction with all of them,
+    /// which sends the merged coin back to the signer
+    async fn merge_gas_coins(
         &self,
         signer: &SuiKeyPair,
-        multisig_pub_key: MultiSigPublicKey,
-        gas_ref: Option<ObjectRef>,
-    ) -> Result<SuiTransactionBlockResponse> {
-        let gas_ref = match gas_ref {
-            Some(g) => g,
-            None => {
-                self.select_gas(
-                    SuiAddress::from(&signer.public()),
-                    None,
-                    TX_GAS_BUDGET,
-                    vec![],
-                )
-                .await?
-            }
+        merge: MergePlan,
+        gas_price: u64,
+        budget: u64,
+    ) -> Result<()> {
+        let owner = SuiAddress::from(&signer.public());
+        let Some(primary) = self.gas_manager.reserve(merge.primary, None) else {
+            return Ok(());
         };
+        // Coins which have been reserved in the meantime are left out
+        let dust: Vec<GasReservation> = merge
+            .dust
+            .into_iter()
+            .filter_map(|object_ref| self.gas_manager.reserve(object_ref, None))
+            .collect();
+        let mut gas_payment = vec![primary.object_ref()];
+        gas_payment.extend(dust.iter().map(GasReservation::object_ref));
 
-        let pubkeys = multisig_pub_key.pubkeys();
-        // TODO: pub struct MultiSig1OutOf2 w/ TryFrom<MultiSigPublicKey> asserts N sigs, weights and threshold
-        if pubkeys.len() != 2 {
-            return Err(anyhow!("Incorrect number of pubkeys in multisig"));
-        }
-        let x_pub_key = &pubkeys[0].0;
-        let x_addr = SuiAddress::from(x_pub_key);
-        let o_pub_key = &pubkeys[1].0;
-        let o_addr = SuiAddress::from(o_pub_key);
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.pay_all_sui(owner);
+        let tx_data = TransactionData::new_programmable(
+            owner,
+            gas_payment,
+            builder.finish(),
+            budget,
+            gas_price,
+        );
+        self.execute_signed(signer, tx_data).await?;
+        Ok(())
+    }
 
+    /// Splits a coin of `amount` out of the reserved coin, sent back to the signer
+    async fn split_gas_coin(
+        &self,
+        signer: &SuiKeyPair,
+        from: &GasReservation<'_>,
+        amount: u64,
+        gas_price: u64,
+        budget: u64,
+    ) -> Result<()> {
+        let owner = SuiAddress::from(&signer.public());
         let mut builder = ProgrammableTransactionBuilder::new();
-        self.0
-            .transaction_builder()
-            .single_move_call(
-                &mut builder,
-                PACKAGE_ID,
-                MODULE_NAME,
-                "create_game",
-                vec![],
-                vec![
-                    SuiJsonValue::from_str(&x_addr.to_string())?,
-                    SuiJsonValue::from_str(&o_addr.to_string())?,
-                ],
-            )
+        builder
+            .pay_sui(vec![owner], vec![amount])
+            .map_err(TicTacToeError::TransactionBuild)?;
+        let tx_data = TransactionData::new_programmable(
+            owner,
+            vec![from.object_ref()],
+            builder.finish(),
+            budget,
+            gas_price,
+        );
+        self.execute_signed(signer, tx_data).await?;
+        Ok(())
+    }
+
+    /// Dry runs the transaction if enabled, failing with its error if it would not succeed. The
+    /// transaction is continued with the estimated budget, or broken off with the response of
+    /// its dry run if it must not be submitted.
+    async fn dry_run(
+        &self,
+        mut tx_data: TransactionData,
+    ) -> Result<ControlFlow<SuiTransactionBlockResponse, TransactionData>> {
+        if !self.dry_run.is_enabled() {
+            return Ok(ControlFlow::Continue(tx_data));
+        }
+        let dry_run = self
+            .client
+            .read_api()
+            .dry_run_transaction_block(tx_data.clone())
             .await?;
-        let pt = builder.finish();
+        if let SuiExecutionStatus::Failure { error } = dry_run.effects.status() {
+            return Err(TicTacToeError::from_execution_failure(
+                error,
+                self.package_id,
+            ));
+        }
 
-        let gas_price = self.0.read_api().get_reference_gas_price().await?;
-        let tx_data = TransactionData::new_programmable_allow_sponsor(
-            SuiAddress::from(&multisig_pub_key),
-            vec![gas_ref],
-            pt,
-            TX_GAS_BUDGET,
-            gas_price,
-            SuiAddress::from(&signer.public()),
+        let estimate = GasEstimate::new(
+            dry_run.effects.gas_cost_summary(),
+            tx_data.gas_price(),
+            self.dry_run.margin_percent(),
         );
+        if let Some(listener) = &self.gas_estimate_listener {
+            listener(&estimate);
+        }
+
+        if self.dry_run == DryRunMode::Only {
+            return Ok(ControlFlow::Break(SuiTransactionBlockResponse {
+                effects: Some(dry_run.effects),
+                events: Some(dry_run.events),
+                object_changes: Some(dry_run.object_changes),
+                balance_changes: Some(dry_run.balance_changes),
+                ..SuiTransactionBlockResponse::new(tx_data.digest())
+            }));
+        }
+        tx_data.gas_data_mut().budget = estimate.budget;
+        Ok(ControlFlow::Continue(tx_data))
+    }
 
+    /// Signs a transaction with the signer alone and executes it
+    async fn execute_signed(
+        &self,
+        signer: &SuiKeyPair,
+        tx_data: TransactionData,
+    ) -> Result<SuiTransactionBlockResponse> {
         let intent = Intent::sui_transaction();
-        let signer_sig =
-            Signature::new_secure(&IntentMessage::new(intent.clone(), &tx_data), signer);
-        let multisig_generic_sig: GenericSignature =
-            MultiSig::combine(vec![signer_sig.clone()], multisig_pub_key)?.into();
-        let sigs = vec![
-            GenericSignature::Signature(signer_sig),
-            multisig_generic_sig,
-        ];
-        let transaction = Transaction::from_generic_sig_data(tx_data, intent, sigs);
-
-        self.0
-            .quorum_driver_api()
-            .execute_transaction_block(
-                transaction,
-                SuiTransactionBlockResponseOptions::new()
-                    .with_effects()
-                    .with_events()
-                    .with_input()
-                    .with_object_changes()
-                    .with_balance_changes(),
-                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
-            )
+        let sig = Signature::new_secure(&IntentMessage::new(intent.clone(), &tx_data), signer);
+        self.execute_transaction(Transaction::from_data(tx_data, intent, vec![sig]))
+            .await
+    }
+
+    /// Decodes the BCS contents of an object into `T`, checking that it is of the type of `T`
+    fn decode<T: MoveStruct>(&self, obj: &SuiObjectData) -> Result<T> {
+        let raw = obj
+            .bcs
+            .as_ref()
+            .ok_or_else(|| TicTacToeError::InvalidLayout {
+                struct_name: T::STRUCT_NAME,
+                reason: "bcs field is unexpectedly empty".to_string(),
+            })?
+            .try_as_move()
+            .ok_or_else(|| TicTacToeError::InvalidLayout {
+                struct_name: T::STRUCT_NAME,
+                reason: "Object is a package".to_string(),
+            })?;
+        decode_move_struct(self.package_id, &raw.type_, &raw.bcs_bytes)
+    }
+
+    /// Objects owned by `owner` which match `query`, after the `start` cursor, fetched page by page
+    /// as they are consumed
+    fn owned_objects(
+        &self,
+        owner: SuiAddress,
+        query: SuiObjectResponseQuery,
+        start: Option<ObjectID>,
+    ) -> impl Stream<Item = Result<SuiObjectResponse>> + '_ {
+        paginate_from(start, move |cursor| {
+            let query = query.clone();
+            async move {
+                self.client
+                    .read_api()
+                    .get_owned_objects(owner, Some(query), cursor, None)
+                    .await
+                    .map_err(TicTacToeError::from)
+            }
+        })
+    }
+
+    // ========================== Contract calls ==========================
+
+    pub async fn create_game(
+        &self,
+        signer: &SuiKeyPair,
+        multisig: &MultiSig1OutOfN,
+        gas_ref: Option<ObjectRef>,
+    ) -> Result<SuiTransactionBlockResponse> {
+        self.execute_multisig_call(signer, multisig, gas_ref, MultisigCall::CreateGame)
             .await
-            .map_err(|e| anyhow!(e))
     }
 
     pub async fn send_mark_to_game(
         &self,
         signer: &SuiKeyPair,
+        gas_ref: Option<ObjectRef>,
         mark_id: ObjectID,
         row: Row,
         col: Col,
     ) -> Result<SuiTransactionBlockResponse> {
         let signer_addr = SuiAddress::from(&signer.public());
+        let reservation;
+        let gas_ref = match gas_ref {
+            Some(gas_ref) => gas_ref,
+            None => {
+                reservation = self
+                    .reserve_gas(signer, Some(mark_id), TX_GAS_BUDGET, vec![])
+                    .await?;
+                reservation.object_ref()
+            }
+        };
         let send_mark_call = self
-            .0
+            .client
             .transaction_builder()
             .move_call(
                 signer_addr,
-                PACKAGE_ID,
+                self.package_id,
                 MODULE_NAME,
                 "send_mark_to_game",
                 vec![],
                 vec![
                     SuiJsonValue::from_object_id(mark_id),
-                    SuiJsonValue::new(serde_json::to_value(Into::<u8>::into(row))?)?,
-                    SuiJsonValue::new(serde_json::to_value(Into::<u8>::into(col))?)?,
+                    SuiJsonValue::new(Into::<u8>::into(row).into())
+                        .map_err(TicTacToeError::TransactionBuild)?,
+                    SuiJsonValue::new(Into::<u8>::into(col).into())
+                        .map_err(TicTacToeError::TransactionBuild)?,
                 ],
-                None,
+                Some(gas_ref.0),
                 TX_GAS_BUDGET,
             )
-            .await?;
-
-        let intent = Intent::sui_transaction();
-        let sig =
-            Signature::new_secure(&IntentMessage::new(intent.clone(), &send_mark_call), signer);
-
-        self.0
-            .quorum_driver_api()
-            .execute_transaction_block(
-                Transaction::from_data(send_mark_call, intent, vec![sig]),
-                SuiTransactionBlockResponseOptions::new().with_effects(),
-                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
-            )
             .await
-            .map_err(|e| anyhow!(e))
+            .map_err(TicTacToeError::TransactionBuild)?;
+
+        match self.dry_run(send_mark_call).await? {
+            ControlFlow::Continue(send_mark_call) => {
+                self.execute_signed(signer, send_mark_call).await
+            }
+            ControlFlow::Break(dry_run) => Ok(dry_run),
+        }
     }
 
     pub async fn place_mark(
         &self,
         signer: &SuiKeyPair,
-        multisig_pub_key: MultiSigPublicKey,
+        multisig: &MultiSig1OutOfN,
         gas_ref: Option<ObjectRef>,
         game_id: ObjectID,
         mark_id: ObjectID,
     ) -> Result<SuiTransactionBlockResponse> {
-        let gas_ref = match gas_ref {
-            Some(g) => g,
-            None => {
-                self.select_gas(
-                    SuiAddress::from(&signer.public()),
-                    None,
-                    TX_GAS_BUDGET,
-                    vec![],
-                )
-                .await?
-            }
-        };
+        let call = MultisigCall::PlaceMark { game_id, mark_id };
+        self.execute_multisig_call(signer, multisig, gas_ref, call)
+            .await
+    }
 
-        // TODO: pub struct MultiSig1OutOf2 w/ TryFrom<MultiSigPublicKey> asserts N sigs, weights and threshold
-        debug_assert!(
-            multisig_pub_key.pubkeys().len() == 2,
-            "Incorrect number of pubkeys in multisig"
-        );
+    /// Note that game should be finished
+    pub async fn delete_game(
+        &self,
+        signer: &SuiKeyPair,
+        multisig: &MultiSig1OutOfN,
+        gas_ref: Option<ObjectRef>,
+        game_id: ObjectID,
+    ) -> Result<SuiTransactionBlockResponse> {
+        let call = MultisigCall::DeleteGame { game_id };
+        self.execute_multisig_call(signer, multisig, gas_ref, call)
+            .await
+    }
 
-        let mut builder = ProgrammableTransactionBuilder::new();
-        self.0
+    /// Deletes a trophy owned by the signer
+    pub async fn burn_trophy(
+        &self,
+        signer: &SuiKeyPair,
+        trophy_id: ObjectID,
+    ) -> Result<SuiTransactionBlockResponse> {
+        let signer_addr = SuiAddress::from(&signer.public());
+        let gas = self
+            .reserve_gas(signer, None, TX_GAS_BUDGET, vec![])
+            .await?;
+        let delete_trophy_call = self
+            .client
             .transaction_builder()
-            .single_move_call(
-                &mut builder,
-                PACKAGE_ID,
+            .move_call(
+                signer_addr,
+                self.package_id,
                 MODULE_NAME,
-                "place_mark",
+                "delete_trophy",
                 vec![],
-                vec![
-                    SuiJsonValue::from_object_id(game_id),
-                    SuiJsonValue::from_object_id(mark_id),
-                ],
+                vec![SuiJsonValue::from_object_id(trophy_id)],
+                Some(gas.object_ref().0),
+                TX_GAS_BUDGET,
             )
+            .await
+            .map_err(TicTacToeError::TransactionBuild)?;
+
+        match self.dry_run(delete_trophy_call).await? {
+            ControlFlow::Continue(delete_trophy_call) => {
+                self.execute_signed(signer, delete_trophy_call).await
+            }
+            ControlFlow::Break(dry_run) => Ok(dry_run),
+        }
+    }
+
+    /// Calls a function of the contract from the multisig account, sponsored by the signer.
+    async fn execute_multisig_call(
+        &self,
+        signer: &SuiKeyPair,
+        multisig: &MultiSig1OutOfN,
+        gas_ref: Option<ObjectRef>,
+        call: MultisigCall,
+    ) -> Result<SuiTransactionBlockResponse> {
+        let reservation;
+        let gas_ref = match gas_ref {
+            Some(gas_ref) => gas_ref,
+            None => {
+                reservation = self
+                    .reserve_gas(signer, None, TX_GAS_BUDGET, vec![])
+                    .await?;
+                reservation.object_ref()
+            }
+        };
+
+        let sponsor = SuiAddress::from(&signer.public());
+        let tx_data = self
+            .multisig_call_data(sponsor, multisig, gas_ref, call)
             .await?;
-        let pt = builder.finish();
+        let tx_data = match self.dry_run(tx_data).await? {
+            ControlFlow::Continue(tx_data) => tx_data,
+            ControlFlow::Break(dry_run) => return Ok(dry_run),
+        };
 
-        let gas_price = self.0.read_api().get_reference_gas_price().await?;
-        let tx_data = TransactionData::new_programmable_allow_sponsor(
-            SuiAddress::from(&multisig_pub_key),
-            vec![gas_ref],
-            pt,
-            TX_GAS_BUDGET,
-            gas_price,
-            SuiAddress::from(&signer.public()),
-        );
+        let transaction = multisig
+            .sign_sponsored_transaction(signer, tx_data)
+            .map_err(TicTacToeError::Signing)?;
+        self.execute_transaction(transaction).await
+    }
 
-        let intent = Intent::sui_transaction();
-        let signer_sig =
-            Signature::new_secure(&IntentMessage::new(intent.clone(), &tx_data), signer);
-        let multisig_generic_sig: GenericSignature =
-            MultiSig::combine(vec![signer_sig.clone()], multisig_pub_key)?.into();
-        let sigs = vec![
-            GenericSignature::Signature(signer_sig),
-            multisig_generic_sig,
-        ];
-        let transaction = Transaction::from_generic_sig_data(tx_data, intent, sigs);
-
-        self.0
+    /// Builds a call of the multisig account sponsored by `sponsor`, without signing it, so that it
+    /// can be signed elsewhere. It is paid with a gas coin of the sponsor, which is not reserved
+    /// as the transaction is not executed here.
+    pub async fn build_multisig_call(
+        &self,
+        sponsor: SuiAddress,
+        multisig: &MultiSig1OutOfN,
+        call: MultisigCall,
+    ) -> Result<TransactionData> {
+        let coins = self.gas_coins(sponsor).await?;
+        let Some(GasPlan::Use(gas_ref)) = self.gas_manager.plan(None, &coins, TX_GAS_BUDGET, &[])
+        else {
+            return Err(TicTacToeError::NoGasCoin {
+                address: sponsor,
+                budget: TX_GAS_BUDGET,
+            });
+        };
+        let tx_data = self
+            .multisig_call_data(sponsor, multisig, gas_ref, call)
+            .await?;
+        match self.dry_run(tx_data.clone()).await? {
+            ControlFlow::Continue(tx_data) => Ok(tx_data),
+            // The transaction is only checked, and exported as it is
+            ControlFlow::Break(_) => Ok(tx_data),
+        }
+    }
+
+    /// Executes a transaction signed by all its signers
+    pub async fn execute_transaction(
+        &self,
+        transaction: Transaction,
+    ) -> Result<SuiTransactionBlockResponse> {
+        let digest = *transaction.digest();
+        self.notify(TransactionEvent::Submitted(digest));
+        let res = self
+            .client
             .quorum_driver_api()
             .execute_transaction_block(
                 transaction,
@@ -296,184 +585,501 @@ impl TicTacToeClient {
                 Some(ExecuteTransactionRequestType::WaitForLocalExecution),
             )
             .await
-            .map_err(|e| anyhow!(e))
+            .map_err(TicTacToeError::from)
+            .and_then(|resp| successful(resp, self.package_id));
+        // The transaction may still be executed if the RPC failed
+        if !matches!(res, Err(TicTacToeError::Rpc(_))) {
+            self.notify(TransactionEvent::Settled(digest));
+        }
+        res
     }
 
-    /// Note that game should be finished
-    pub fn delete_game(
+    fn notify(&self, event: TransactionEvent) {
+        if let Some(listener) = &self.transaction_listener {
+            listener(event);
+        }
+    }
+
+    /// Execution status of a submitted transaction. `TransactionNotFound` if it was not executed.
+    pub async fn transaction_status(
         &self,
-        signer: &SuiKeyPair,
-        multisig_pub_key: &MultiSigPublicKey,
+        digest: TransactionDigest,
+    ) -> Result<SuiExecutionStatus> {
+        // Unlike a single one, transactions fetched together are left out if they were not executed
+        let resps = self
+            .client
+            .read_api()
+            .multi_get_transactions_with_options(
+                vec![digest],
+                SuiTransactionBlockResponseOptions::new().with_effects(),
+            )
+            .await
+            .map_err(|e| {
+                // Last resort, for the nodes which fail instead
+                if e.to_string().contains(TRANSACTION_NOT_FOUND) {
+                    TicTacToeError::TransactionNotFound(digest)
+                } else {
+                    e.into()
+                }
+            })?;
+        resps
+            .into_iter()
+            .find(|resp| resp.digest == digest)
+            .and_then(|resp| resp.effects)
+            .map(|effects| effects.status().clone())
+            .ok_or(TicTacToeError::TransactionNotFound(digest))
+    }
+
+    async fn multisig_call_data(
+        &self,
+        sponsor: SuiAddress,
+        multisig: &MultiSig1OutOfN,
         gas_ref: ObjectRef,
-        game_id: ObjectID,
-    ) -> Result<SuiTransactionBlockResponse> {
-        println!(
-            "delete_game(signer: {}, multisig_pub_key: {}, gas_ref: ({}, {}, {}), game_id: {})",
-            signer.public().encode_base64(),
-            multisig_pub_key.to_string(),
-            gas_ref.0,
-            gas_ref.1,
-            gas_ref.2,
-            game_id
-        );
-        dbg!(signer);
-        dbg!(multisig_pub_key);
-        dbg!(game_id);
+        call: MultisigCall,
+    ) -> Result<TransactionData> {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        self.client
+            .transaction_builder()
+            .single_move_call(
+                &mut builder,
+                self.package_id,
+                MODULE_NAME,
+                call.function(),
+                vec![],
+                call.call_args(multisig)?,
+            )
+            .await
+            .map_err(TicTacToeError::TransactionBuild)?;
+        let pt = builder.finish();
 
-        todo!();
+        let gas_price = self.client.read_api().get_reference_gas_price().await?;
+        Ok(TransactionData::new_programmable_allow_sponsor(
+            multisig.admin_address(),
+            vec![gas_ref],
+            pt,
+            TX_GAS_BUDGET,
+            gas_price,
+            sponsor,
+        ))
     }
 
     // ============================ Query calls ============================
 
     pub async fn fetch_available_game(
         &self,
-        multisig_pub_key: &MultiSigPublicKey,
+        multisig: &MultiSig1OutOfN,
         fetch_filter: Option<&FetchGameFilter>,
     ) -> Result<TicTacToe> {
-        let filter = SuiObjectDataFilter::StructType(StructTag {
-            address: PACKAGE_ID.into(),
-            module: Identifier::from_str(MODULE_NAME)?,
-            name: Identifier::from_str(TIC_TAC_TOE_STRUCT_NAME)?,
-            type_params: vec![],
-        });
-
-        // TODO: replace with bcs.
-        // From content is way more verbose as we need to get to fields and then iter the fields.
-        let query =
-            SuiObjectResponseQuery::new(Some(filter), Some(SuiObjectDataOptions::full_content()));
-
-        // TODO: cursor
-        let rpc_res = self
-            .0
-            .read_api()
-            .get_owned_objects(SuiAddress::from(multisig_pub_key), Some(query), None, None)
-            .await?;
+        self.fetch_games(multisig, fetch_filter)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(TicTacToeError::NoAvailableGames)
+    }
 
-        rpc_res
-            .data
-            .iter()
-            .find_map(|obj_resp| {
-                let fields = match obj_resp.data.as_ref() {
-                    Some(SuiObjectData {
-                        content:
-                            Some(SuiParsedData::MoveObject(SuiParsedMoveObject {
-                                fields: SuiMoveStruct::WithFields(fields),
-                                ..
-                            })),
-                        ..
-                    }) => fields,
-                    _ => {
-                        return None;
-                    }
-                };
+    /// All the games owned by the multisig account which pass the filter
+    pub async fn fetch_games(
+        &self,
+        multisig: &MultiSig1OutOfN,
+        fetch_filter: Option<&FetchGameFilter>,
+    ) -> Result<Vec<TicTacToe>> {
+        let filter = SuiObjectDataFilter::StructType(TicTacToe::struct_tag(self.package_id));
 
-                let Ok(game) = TicTacToe::try_from(fields) else {
-                    return None;
-                };
+        let query =
+            SuiObjectResponseQuery::new(Some(filter), Some(SuiObjectDataOptions::new().with_bcs()));
 
-                if let Some(fetch_filter) = fetch_filter {
-                    fetch_filter.filter(game)
-                } else {
-                    Some(game)
-                }
+        self.owned_objects(multisig.admin_address(), query, None)
+            .try_filter_map(|obj_resp| {
+                let game = obj_resp
+                    .object()
+                    .map_err(TicTacToeError::ObjectResponse)
+                    .and_then(|obj| self.decode::<TicTacToe>(obj))
+                    .map(|game| match fetch_filter {
+                        Some(fetch_filter) => fetch_filter.filter(game),
+                        None => Some(game),
+                    });
+                future::ready(game)
             })
-            .ok_or(anyhow!("No available games"))
+            .try_collect()
+            .await
     }
 
     pub async fn fetch_game(&self, game_id: ObjectID) -> Result<TicTacToe> {
         // Get the raw BCS serialised move object data
         let current_game = self
-            .0
+            .client
             .read_api()
             .get_object_with_options(game_id, SuiObjectDataOptions::new().with_bcs())
             .await?;
-        current_game
-            .object()?
-            .bcs
-            .as_ref()
-            .ok_or(anyhow!("bcs field is unexpectedly empty"))?
-            .try_as_move()
-            .ok_or(anyhow!("Cannot parse move object to game object"))?
-            .deserialize()
+        let game = current_game
+            .object()
+            .map_err(|_| TicTacToeError::GameNotFound(game_id))?;
+        self.decode(game)
     }
 
     pub async fn find_mark(&self, game_id: ObjectID, owner_addr: SuiAddress) -> Result<Mark> {
         let query = SuiObjectResponseQuery {
-            filter: Some(SuiObjectDataFilter::StructType(StructTag {
-                address: PACKAGE_ID.into(),
-                module: Identifier::from_str(MODULE_NAME)?,
-                name: Identifier::from_str(MARK_STRUCT_NAME)?,
-                type_params: vec![],
-            })),
+            filter: Some(SuiObjectDataFilter::StructType(Mark::struct_tag(
+                self.package_id,
+            ))),
             options: Some(SuiObjectDataOptions::new().with_bcs()),
         };
-        // TODO cursor
-        let mark = self
-            .0
+        let marks = self.owned_objects(owner_addr, query, None);
+        pin_mut!(marks);
+
+        while let Some(obj_resp) = marks.try_next().await? {
+            let obj = obj_resp.object().map_err(TicTacToeError::ObjectResponse)?;
+            let mark: Mark = self.decode(obj)?;
+            if mark.game_id.bytes == game_id {
+                return Ok(mark);
+            }
+        }
+        Err(TicTacToeError::MarkNotFound)
+    }
+
+    /// A page of at most `limit` trophies owned by `owner_addr`, starting after `cursor`. The pages
+    /// of the node are followed until the page is filled, as the node may return fewer objects.
+    pub async fn fetch_trophies(
+        &self,
+        owner_addr: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> Result<TrophyPage> {
+        let query = SuiObjectResponseQuery {
+            filter: Some(SuiObjectDataFilter::StructType(
+                TicTacToeTrophy::struct_tag(self.package_id),
+            )),
+            options: Some(SuiObjectDataOptions::new().with_bcs()),
+        };
+        let trophies = self
+            .owned_objects(owner_addr, query, cursor)
+            .and_then(|obj_resp| {
+                future::ready(
+                    obj_resp
+                        .object()
+                        .map_err(TicTacToeError::ObjectResponse)
+                        .and_then(|obj| self.decode::<TicTacToeTrophy>(obj)),
+                )
+            });
+        take_page(trophies, limit, |trophy| trophy.id).await
+    }
+
+    pub async fn fetch_trophy(&self, trophy_id: ObjectID) -> Result<TicTacToeTrophy> {
+        let trophy = self
+            .client
             .read_api()
-            .get_owned_objects(owner_addr, Some(query), None, None)
+            .get_object_with_options(trophy_id, SuiObjectDataOptions::new().with_bcs())
             .await?;
-
-        mark.data
-            .iter()
-            .find_map(|obj_resp| {
-                let Some(obj_data) = obj_resp.object().ok() else {
-                    println!("No reference to the object");
-                    return None;
-                };
-                let Some(sui_raw_data) = obj_data.bcs.as_ref() else {
-                    println!("No bcs data");
-                    return None;
-                };
-                let Some(sui_raw_move_obj) = sui_raw_data.try_as_move() else {
-                    println!("Object is package");
-                    return None;
-                };
-                let Ok(mark): Result<Mark> = sui_raw_move_obj.deserialize() else {
-                    println!("Cannot deserialize");
-                    return None;
-                };
-                if mark.game_id.bytes == game_id {
-                    Some(mark)
-                } else {
-                    None
-                }
-            })
-            .ok_or(anyhow!("No mark found"))
+        let trophy = trophy
+            .object()
+            .map_err(|_| TicTacToeError::TrophyNotFound(trophy_id))?;
+        self.decode(trophy)
     }
 
     pub async fn mark_owner(&self, mark_id: ObjectID) -> Result<SuiAddress> {
         let mark = self
-            .0
+            .client
             .read_api()
             .get_object_with_options(mark_id, SuiObjectDataOptions::new().with_owner())
             .await?;
-        match mark
+        match mark.data.ok_or(TicTacToeError::MarkNotFound)?.owner {
+            Some(Owner::AddressOwner(owner_addr)) => Ok(owner_addr),
+            _ => Err(TicTacToeError::NotAddressOwned(mark_id)),
+        }
+    }
+
+    /// The multisig account which owns the game
+    pub async fn game_owner(&self, game_id: ObjectID) -> Result<SuiAddress> {
+        let resp = self
+            .client
+            .read_api()
+            .get_object_with_options(game_id, SuiObjectDataOptions::new().with_owner())
+            .await?;
+        let game = resp.data.ok_or(TicTacToeError::GameNotFound(game_id))?;
+        match game.owner {
+            Some(Owner::AddressOwner(owner_addr)) => Ok(owner_addr),
+            _ => Err(TicTacToeError::NotAddressOwned(game_id)),
+        }
+    }
+
+    /// Version of the game object, which bumps on every placed mark. Cheaper than `fetch_game`, as
+    /// it does not fetch the contents.
+    pub async fn game_version(&self, game_id: ObjectID) -> Result<SequenceNumber> {
+        let game = self
+            .client
+            .read_api()
+            .get_object_with_options(game_id, SuiObjectDataOptions::new())
+            .await?;
+        Ok(game
             .data
-            .ok_or(anyhow!("No mark found"))?
-            .owner
-            .ok_or(anyhow!("No owner found"))?
-        {
-            Owner::AddressOwner(owner_addr) => Ok(owner_addr),
-            _ => Err(anyhow!("Owner is not an address!")),
+            .ok_or(TicTacToeError::GameNotFound(game_id))?
+            .version)
+    }
+
+    /// Notifies of every transaction changing one of `objects`, as pushed by the websocket of the
+    /// fullnode. The sender is dropped once the subscription fails, for the receiver to poll then.
+    pub fn subscribe_changes(&self, objects: Vec<ObjectID>) -> watch::Receiver<u64> {
+        let (changes, receiver) = watch::channel(0);
+        let (rpc_url, ws_url) = (self.rpc_url.clone(), self.ws_url.clone());
+        tokio::spawn(async move {
+            if let Err(e) = forward_changes(&rpc_url, &ws_url, objects, &changes).await {
+                eprintln!(
+                    "Could not subscribe to the changes of the game, polling for them instead: {}",
+                    e
+                );
+            }
+        });
+        receiver
+    }
+
+    /// Successful transactions sending and placing the mark of a game, in no particular order
+    pub async fn mark_transactions(&self, game_id: ObjectID) -> Result<Vec<MarkTransaction>> {
+        let mark_id = self.game_mark_id(game_id).await?;
+        let query = SuiTransactionBlockResponseQuery::new(
+            Some(TransactionFilter::InputObject(mark_id)),
+            Some(
+                SuiTransactionBlockResponseOptions::new()
+                    .with_input()
+                    .with_effects(),
+            ),
+        );
+        let txs: Vec<SuiTransactionBlockResponse> =
+            self.query_transactions(query).try_collect().await?;
+        Ok(txs
+            .iter()
+            .filter_map(|tx| self.mark_transaction(tx, mark_id))
+            .collect())
+    }
+
+    /// The mark is created along with the game, by the first transaction changing the game. Unlike
+    /// the mark, which is deleted once the game is finished, that transaction is always there.
+    async fn game_mark_id(&self, game_id: ObjectID) -> Result<ObjectID> {
+        let query = SuiTransactionBlockResponseQuery::new(
+            Some(TransactionFilter::ChangedObject(game_id)),
+            Some(SuiTransactionBlockResponseOptions::new().with_effects()),
+        );
+        let page = self
+            .client
+            .read_api()
+            .query_transaction_blocks(query, None, Some(1), false)
+            .await?;
+        let effects = page
+            .data
+            .into_iter()
+            .next()
+            .and_then(|tx| tx.effects)
+            .ok_or(TicTacToeError::GameNotFound(game_id))?;
+        effects
+            .created()
+            .iter()
+            .map(|obj_ref| obj_ref.object_id())
+            .find(|object_id| *object_id != game_id)
+            .ok_or(TicTacToeError::MarkNotFound)
+    }
+
+    fn query_transactions(
+        &self,
+        query: SuiTransactionBlockResponseQuery,
+    ) -> impl Stream<Item = Result<SuiTransactionBlockResponse>> + '_ {
+        paginate(move |cursor| {
+            let query = query.clone();
+            async move {
+                self.client
+                    .read_api()
+                    .query_transaction_blocks(query, cursor, None, false)
+                    .await
+                    .map_err(TicTacToeError::from)
+            }
+        })
+    }
+
+    /// Decodes the call of the contract taking the mark. `None` if the transaction failed, as the
+    /// mark was left as it was, or if it does not call the contract.
+    fn mark_transaction(
+        &self,
+        tx: &SuiTransactionBlockResponse,
+        mark_id: ObjectID,
+    ) -> Option<MarkTransaction> {
+        if !matches!(tx.effects.as_ref()?.status(), SuiExecutionStatus::Success) {
+            return None;
         }
+        let data = &tx.transaction.as_ref()?.data;
+        let SuiTransactionBlockKind::ProgrammableTransaction(ptb) = data.transaction() else {
+            return None;
+        };
+        let mark_version = ptb.inputs.iter().find_map(|input| match input {
+            SuiCallArg::Object(SuiObjectArg::ImmOrOwnedObject {
+                object_id, version, ..
+            }) if *object_id == mark_id => Some(*version),
+            _ => None,
+        })?;
+        // Pure u8 arguments of the call
+        let pure_u8 = |arg: &SuiArgument| match arg {
+            SuiArgument::Input(index) => ptb
+                .inputs
+                .get(*index as usize)?
+                .pure()?
+                .to_json_value()
+                .as_u64()
+                .and_then(|value| u8::try_from(value).ok()),
+            _ => None,
+        };
+        let call = ptb.commands.iter().find_map(|command| match command {
+            SuiCommand::MoveCall(call)
+                if call.package == self.package_id && call.module == MODULE_NAME =>
+            {
+                match (call.function.as_str(), call.arguments.as_slice()) {
+                    ("send_mark_to_game", [_, row, col]) => Some(MarkCall::Send {
+                        row: pure_u8(row)?,
+                        col: pure_u8(col)?,
+                    }),
+                    ("place_mark", _) => Some(MarkCall::Place),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })?;
+        Some(MarkTransaction {
+            digest: tx.digest,
+            sender: *data.sender(),
+            timestamp_ms: tx.timestamp_ms,
+            mark_version,
+            call,
+        })
+    }
+
+    /// The current epoch and how long until it ends, as estimated from its duration
+    pub async fn epoch_end(&self) -> Result<(u64, Duration)> {
+        let state = self
+            .client
+            .governance_api()
+            .get_latest_sui_system_state()
+            .await?;
+        let end_ms = state.epoch_start_timestamp_ms + state.epoch_duration_ms;
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        Ok((
+            state.epoch,
+            Duration::from_millis(end_ms.saturating_sub(now_ms)),
+        ))
+    }
+}
+
+/// Counts the transactions changing one of `objects` into `changes`, until the receivers or the
+/// subscription are gone
+async fn forward_changes(
+    rpc_url: &str,
+    ws_url: &str,
+    objects: Vec<ObjectID>,
+    changes: &watch::Sender<u64>,
+) -> Result<()> {
+    let client = SuiClientBuilder::default()
+        .ws_url(ws_url)
+        .build(rpc_url)
+        .await?;
+    let mut subscriptions = Vec::with_capacity(objects.len());
+    for object in objects {
+        let effects = client
+            .read_api()
+            .subscribe_transaction(TransactionFilter::ChangedObject(object))
+            .await?;
+        subscriptions.push(effects.boxed());
+    }
+    let mut effects = stream::select_all(subscriptions);
+    // Changes made while subscribing are not pushed, so have them checked
+    changes.send_modify(|count| *count += 1);
+    loop {
+        tokio::select! {
+            next = effects.next() => match next {
+                Some(change) => {
+                    change?;
+                    changes.send_modify(|count| *count += 1);
+                }
+                // Closed by the fullnode, and polled from then on
+                None => return Ok(()),
+            },
+            _ = changes.closed() => return Ok(()),
+        }
+    }
+}
+
+/// Fails with the error of the transaction if it was not executed successfully, decoding the aborts
+/// of the contract published as `package_id`
+fn successful(
+    resp: SuiTransactionBlockResponse,
+    package_id: ObjectID,
+) -> Result<SuiTransactionBlockResponse> {
+    if !resp.errors.is_empty() {
+        return Err(TicTacToeError::ExecutionFailed(resp.errors.join(", ")));
+    }
+    match resp.effects.as_ref().map(|effects| effects.status()) {
+        Some(SuiExecutionStatus::Success) => Ok(resp),
+        Some(SuiExecutionStatus::Failure { error }) => {
+            Err(TicTacToeError::from_execution_failure(error, package_id))
+        }
+        None => Err(TicTacToeError::ExecutionFailed("No effects".to_string())),
+    }
+}
+
+/// Functions of the contract called by the multisig account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultisigCall {
+    /// Creates a new game, passing its mark to player X
+    CreateGame,
+    /// Places the mark sent to the game
+    PlaceMark {
+        game_id: ObjectID,
+        mark_id: ObjectID,
+    },
+    /// Deletes a finished game
+    DeleteGame { game_id: ObjectID },
+}
+
+impl MultisigCall {
+    pub fn function(&self) -> &'static str {
+        match self {
+            MultisigCall::CreateGame => "create_game",
+            MultisigCall::PlaceMark { .. } => "place_mark",
+            MultisigCall::DeleteGame { .. } => "delete_game",
+        }
+    }
+
+    fn call_args(&self, multisig: &MultiSig1OutOfN) -> Result<Vec<SuiJsonValue>> {
+        Ok(match *self {
+            MultisigCall::CreateGame => vec![
+                SuiJsonValue::from_str(&multisig.x_address().to_string())
+                    .map_err(TicTacToeError::TransactionBuild)?,
+                SuiJsonValue::from_str(&multisig.o_address().to_string())
+                    .map_err(TicTacToeError::TransactionBuild)?,
+            ],
+            MultisigCall::PlaceMark { game_id, mark_id } => vec![
+                SuiJsonValue::from_object_id(game_id),
+                SuiJsonValue::from_object_id(mark_id),
+            ],
+            MultisigCall::DeleteGame { game_id } => vec![SuiJsonValue::from_object_id(game_id)],
+        })
     }
 }
 
 pub enum FetchGameFilter {
-    FinishedFilter(bool),
+    /// Keeps the games which are still being played
+    InProgress,
+    /// Keeps the games which are won or drawn
+    Finished,
 }
 
 impl FetchGameFilter {
     pub fn filter(&self, game: TicTacToe) -> Option<TicTacToe> {
-        match self {
-            Self::FinishedFilter(finished) => {
-                if (game.finished == 0) == *finished {
-                    Some(game)
-                } else {
-                    None
-                }
-            }
+        let keep = match self {
+            Self::InProgress => game.finished == 0,
+            Self::Finished => game.finished != 0,
+        };
+        if keep {
+            Some(game)
+        } else {
+            None
         }
     }
 }
@@ -0,0 +1,159 @@
+//! The states a player goes through during their turns, driven by the observed on-chain state of
+//! the game and by the outcome of the player's own transactions.
+
+use std::fmt::Display;
+
+use anyhow::Result;
+use sui_types::base_types::SuiAddress;
+
+use crate::contract_structs::TicTacToe;
+use crate::game_engine::{self, GameStatus};
+use crate::startup::Player;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Won(Player),
+    Draw,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Won(player) => write!(f, "{} won!", player),
+            Outcome::Draw => write!(f, "Draw"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnState {
+    AwaitingOpponent,
+    /// The mark is ours, so the next move should be sent to the game
+    MyTurnNeedInput,
+    /// The mark is owned by the multisig account, so it only needs to be placed
+    MarkSentAwaitingPlace,
+    /// Waiting for the placed mark to show up on chain
+    PlaceSubmitted,
+    Finished(Outcome),
+    /// The state is unknown, at start or after a failed transaction, so it is observed right away.
+    /// If it is still inconsistent, the next change is waited for.
+    Recovering,
+}
+
+/// What the player should do next in a given state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnAction {
+    /// Wait for the next change of the game
+    WaitForUpdate,
+    /// Fetch the current state of the game without waiting for a change
+    Observe,
+    SendMark,
+    PlaceMark,
+    Done,
+}
+
+#[derive(Debug, Clone)]
+pub struct TurnStateMachine {
+    player: Player,
+    my_address: SuiAddress,
+    multisig_address: SuiAddress,
+    state: TurnState,
+    /// Whether the game has not been observed since recovering started, so that an inconsistent
+    /// observation waits for the next change instead of being repeated right away
+    needs_observation: bool,
+}
+
+impl TurnStateMachine {
+    pub fn new(player: Player, my_address: SuiAddress, multisig_address: SuiAddress) -> Self {
+        Self {
+            player,
+            my_address,
+            multisig_address,
+            state: TurnState::Recovering,
+            needs_observation: true,
+        }
+    }
+
+    pub fn state(&self) -> TurnState {
+        self.state
+    }
+
+    pub fn next_action(&self) -> TurnAction {
+        match self.state {
+            TurnState::AwaitingOpponent | TurnState::PlaceSubmitted => TurnAction::WaitForUpdate,
+            TurnState::MyTurnNeedInput => TurnAction::SendMark,
+            TurnState::MarkSentAwaitingPlace => TurnAction::PlaceMark,
+            TurnState::Finished(_) => TurnAction::Done,
+            TurnState::Recovering if self.needs_observation => TurnAction::Observe,
+            TurnState::Recovering => TurnAction::WaitForUpdate,
+        }
+    }
+
+    /// Moves to the state of the game on chain, `mark_owner` being `None` if the mark is gone.
+    /// Fails on a game in an invalid state, which observing it again would not fix.
+    pub fn observe(
+        &mut self,
+        game: &TicTacToe,
+        mark_owner: Option<SuiAddress>,
+    ) -> Result<TurnState> {
+        self.needs_observation = false;
+        self.state = match GameStatus::try_from(game.finished)? {
+            GameStatus::Won(player) => TurnState::Finished(Outcome::Won(player)),
+            GameStatus::Draw => TurnState::Finished(Outcome::Draw),
+            GameStatus::InProgress => self.observe_in_progress(game, mark_owner),
+        };
+        Ok(self.state)
+    }
+
+    fn observe_in_progress(&self, game: &TicTacToe, mark_owner: Option<SuiAddress>) -> TurnState {
+        if game_engine::player_of_turn(game.cur_turn) != self.player {
+            return TurnState::AwaitingOpponent;
+        }
+        match mark_owner {
+            // Our mark came back, either for the next turn or because the move was rejected
+            Some(owner) if owner == self.my_address => TurnState::MyTurnNeedInput,
+            // The placed mark has not shown up yet
+            Some(owner)
+                if owner == self.multisig_address && self.state == TurnState::PlaceSubmitted =>
+            {
+                TurnState::PlaceSubmitted
+            }
+            Some(owner) if owner == self.multisig_address => TurnState::MarkSentAwaitingPlace,
+            // Mark is on its way, or the observation is inconsistent
+            _ => TurnState::Recovering,
+        }
+    }
+
+    pub fn mark_sent(&mut self) -> TurnState {
+        if self.state == TurnState::MyTurnNeedInput {
+            self.state = TurnState::MarkSentAwaitingPlace;
+        }
+        self.state
+    }
+
+    pub fn place_submitted(&mut self) -> TurnState {
+        if self.state == TurnState::MarkSentAwaitingPlace {
+            self.state = TurnState::PlaceSubmitted;
+        }
+        self.state
+    }
+
+    /// The move was rejected as its cell is taken, so the game is observed again before the next
+    /// move
+    pub fn move_rejected(&mut self) -> TurnState {
+        if self.state == TurnState::MyTurnNeedInput {
+            self.state = TurnState::Recovering;
+            self.needs_observation = true;
+        }
+        self.state
+    }
+
+    /// A transaction of the player failed, so the state has to be observed again
+    pub fn transaction_failed(&mut self) -> TurnState {
+        if !matches!(self.state, TurnState::Finished(_)) {
+            self.state = TurnState::Recovering;
+            self.needs_observation = true;
+        }
+        self.state
+    }
+}
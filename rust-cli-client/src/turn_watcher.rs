@@ -0,0 +1,161 @@
+//! Watches a game for changes, so that the player is told when it is their turn.
+//!
+//! Only the version of the game and the owner of the mark are checked for changes, and the game
+//! itself is only fetched when one of them changed. The watcher waits for the changes pushed by the
+//! backend, the Sui client subscribing to the transactions of the game over the websocket of the
+//! fullnode. Without a subscription, or once it is closed, the game is polled with an exponential
+//! backoff instead.
+
+use std::cmp::min;
+use std::time::Duration;
+
+use anyhow::Result;
+use futures::stream::{self, Stream};
+use tokio::sync::watch;
+
+use sui_types::base_types::{SequenceNumber, SuiAddress};
+
+use crate::contract_structs::TicTacToe;
+use crate::error::TicTacToeError;
+use crate::game_backend::{GameBackend, GameInfo};
+use crate::game_engine;
+use crate::startup::Player;
+
+#[derive(Debug, Clone)]
+pub struct PollConfig {
+    /// Interval right after a change
+    pub initial_interval: Duration,
+    /// The interval grows up to this while nothing changes
+    pub max_interval: Duration,
+    pub multiplier: u32,
+}
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        Self {
+            initial_interval: Duration::from_millis(500),
+            max_interval: Duration::from_secs(8),
+            multiplier: 2,
+        }
+    }
+}
+
+/// A game, with the version at which it was seen to change
+#[derive(Debug, Clone)]
+pub struct ObservedGame {
+    pub game: TicTacToe,
+    pub version: SequenceNumber,
+}
+
+#[derive(Debug, Clone)]
+pub enum GameUpdate {
+    MyTurn(ObservedGame),
+    OpponentsTurn(ObservedGame),
+    /// Last update of the stream
+    Finished(ObservedGame),
+}
+
+impl GameUpdate {
+    fn observed(&self) -> &ObservedGame {
+        match self {
+            GameUpdate::MyTurn(observed)
+            | GameUpdate::OpponentsTurn(observed)
+            | GameUpdate::Finished(observed) => observed,
+        }
+    }
+
+    pub fn game(&self) -> &TicTacToe {
+        &self.observed().game
+    }
+
+    pub fn version(&self) -> SequenceNumber {
+        self.observed().version
+    }
+}
+
+struct WatchState<'a, B> {
+    backend: &'a B,
+    game_info: GameInfo,
+    player: Player,
+    poll: PollConfig,
+    interval: Duration,
+    changes: Option<watch::Receiver<u64>>,
+    last_version: Option<SequenceNumber>,
+    last_mark_owner: Option<SuiAddress>,
+    finished: bool,
+}
+
+/// Streams the updates of a game, as seen by `player`: first its current state and then one update
+/// per change, until it is finished.
+pub fn watch_turns<'a, B: GameBackend + Sync>(
+    backend: &'a B,
+    game_info: GameInfo,
+    player: Player,
+    poll: PollConfig,
+) -> impl Stream<Item = Result<GameUpdate>> + 'a {
+    let state = WatchState {
+        backend,
+        changes: backend.subscribe_changes(&game_info),
+        game_info,
+        player,
+        interval: poll.initial_interval,
+        poll,
+        last_version: None,
+        last_mark_owner: None,
+        finished: false,
+    };
+    stream::unfold(state, |mut state| async move {
+        if state.finished {
+            return None;
+        }
+        let update = state.next_update().await;
+        Some((update, state))
+    })
+}
+
+impl<B: GameBackend + Sync> WatchState<'_, B> {
+    async fn next_update(&mut self) -> Result<GameUpdate> {
+        loop {
+            let version = self.backend.game_version(self.game_info.game_id).await?;
+            let mark_owner = match self.backend.mark_owner(self.game_info.mark_id).await {
+                Ok(owner) => Some(owner),
+                // The mark is deleted at the end of the game
+                Err(TicTacToeError::MarkNotFound) => None,
+                Err(e) => return Err(e.into()),
+            };
+            if self.last_version != Some(version) || self.last_mark_owner != mark_owner {
+                self.last_version = Some(version);
+                self.last_mark_owner = mark_owner;
+                self.interval = self.poll.initial_interval;
+                let game = self.backend.fetch_game(self.game_info.game_id).await?;
+                return Ok(self.classify(ObservedGame { game, version }));
+            }
+            self.wait().await;
+        }
+    }
+
+    fn classify(&mut self, observed: ObservedGame) -> GameUpdate {
+        let game = &observed.game;
+        if game.finished != 0 {
+            self.finished = true;
+            GameUpdate::Finished(observed)
+        } else if game_engine::player_of_turn(game.cur_turn) == self.player {
+            GameUpdate::MyTurn(observed)
+        } else {
+            GameUpdate::OpponentsTurn(observed)
+        }
+    }
+
+    /// Waits for the next change pushed by the backend, or for the poll interval without one
+    async fn wait(&mut self) {
+        if let Some(changes) = &mut self.changes {
+            // The backend cannot push its changes anymore, so fall back to polling
+            if changes.changed().await.is_err() {
+                self.changes = None;
+            }
+            return;
+        }
+        tokio::time::sleep(self.interval).await;
+        self.interval = min(self.interval * self.poll.multiplier, self.poll.max_interval);
+    }
+}
@@ -0,0 +1,131 @@
+//! Plays the bots against each other on the Rust game engine.
+
+use rust_cli_client::ai::{board_of, scores, Bot, BotLevel, DEFAULT_BLUNDER_PERCENT};
+use rust_cli_client::game_engine::{Board, GameStatus, MARK_EMPTY, MARK_O, MARK_X};
+use rust_cli_client::startup::Player;
+
+const GAMES: u64 = 50;
+
+/// Plays a whole game and returns how it ended
+fn play(x_bot: &mut Bot, o_bot: &mut Bot) -> GameStatus {
+    let mut board = Board::new();
+    while let Some(cell) = match board.cur_player() {
+        Player::X => x_bot.choose(&board),
+        Player::O => o_bot.choose(&board),
+    } {
+        board.place_mark(cell).unwrap();
+    }
+    board.status().unwrap()
+}
+
+/// Board of the given column major cells and their marks
+fn board(marks: &[(u8, u8)]) -> Board {
+    let mut gameboard = vec![MARK_EMPTY; 9];
+    for &(cell, mark) in marks {
+        gameboard[cell as usize] = mark;
+    }
+    board_of(&gameboard)
+}
+
+#[test]
+fn test_parse_levels() {
+    assert_eq!("random".parse::<BotLevel>().unwrap(), BotLevel::Random);
+    assert_eq!(
+        "depth:2".parse::<BotLevel>().unwrap(),
+        BotLevel::DepthLimited(2)
+    );
+    assert_eq!(
+        "blunder".parse::<BotLevel>().unwrap(),
+        BotLevel::Blundering(DEFAULT_BLUNDER_PERCENT)
+    );
+    assert_eq!(
+        "blunder:5".parse::<BotLevel>().unwrap(),
+        BotLevel::Blundering(5)
+    );
+    for level in [
+        BotLevel::Greedy,
+        BotLevel::Perfect,
+        BotLevel::DepthLimited(3),
+    ] {
+        assert_eq!(level.to_string().parse::<BotLevel>().unwrap(), level);
+    }
+    assert!("depth".parse::<BotLevel>().is_err());
+    assert!("depth:0".parse::<BotLevel>().is_err());
+    assert!("blunder:101".parse::<BotLevel>().is_err());
+    assert!("perfect:1".parse::<BotLevel>().is_err());
+    assert!("genius".parse::<BotLevel>().is_err());
+}
+
+#[test]
+fn test_board_of() {
+    let board = board(&[(0, MARK_X), (4, MARK_O), (3, MARK_X)]);
+    assert_eq!(board.cur_turn, 3);
+    assert_eq!(board.cur_player(), Player::O);
+}
+
+#[test]
+fn test_win_and_block() {
+    //  X | X |       X to play wins at 6, and O to play blocks it
+    //    | O |
+    //    |   |
+    let x_to_play = board(&[(0, MARK_X), (4, MARK_O), (3, MARK_X), (8, MARK_O)]);
+    let o_to_play = board(&[(0, MARK_X), (4, MARK_O), (3, MARK_X)]);
+    for level in [
+        BotLevel::Greedy,
+        BotLevel::DepthLimited(2),
+        BotLevel::Perfect,
+    ] {
+        let mut bot = Bot::with_seed(level, 0);
+        assert_eq!(bot.choose(&x_to_play), Some(6), "{} wins", level);
+        assert_eq!(bot.choose(&o_to_play), Some(6), "{} blocks", level);
+    }
+    // Looking at its own move only, it sees the win
+    let mut bot = Bot::with_seed(BotLevel::DepthLimited(1), 0);
+    assert_eq!(bot.choose(&x_to_play), Some(6));
+
+    // Sooner wins score higher
+    let scores = scores(&x_to_play, None);
+    let (_, best) = scores.iter().max_by_key(|&&(_, score)| score).unwrap();
+    assert!(scores
+        .iter()
+        .all(|&(cell, score)| cell == 6 || score < *best));
+}
+
+#[test]
+fn test_blunder() {
+    let x_to_play = board(&[(0, MARK_X), (4, MARK_O), (3, MARK_X), (8, MARK_O)]);
+    let mut bot = Bot::with_seed(BotLevel::Blundering(100), 0);
+    assert_ne!(bot.choose(&x_to_play), Some(6));
+    let mut bot = Bot::with_seed(BotLevel::Blundering(0), 0);
+    assert_eq!(bot.choose(&x_to_play), Some(6));
+}
+
+#[test]
+fn test_perfect_never_loses() {
+    for seed in 0..GAMES {
+        let mut perfect = Bot::with_seed(BotLevel::Perfect, seed);
+        let mut random = Bot::with_seed(BotLevel::Random, seed);
+        assert_ne!(play(&mut perfect, &mut random), GameStatus::Won(Player::O));
+        assert_ne!(play(&mut random, &mut perfect), GameStatus::Won(Player::X));
+    }
+
+    let mut x_bot = Bot::with_seed(BotLevel::Perfect, 1);
+    let mut o_bot = Bot::with_seed(BotLevel::Perfect, 2);
+    assert_eq!(play(&mut x_bot, &mut o_bot), GameStatus::Draw);
+}
+
+#[test]
+fn test_stronger_levels_win_more() {
+    let wins = |level| {
+        (0..GAMES)
+            .filter(|&seed| {
+                let mut bot = Bot::with_seed(level, seed);
+                let mut random = Bot::with_seed(BotLevel::Random, seed + GAMES);
+                play(&mut bot, &mut random) == GameStatus::Won(Player::X)
+            })
+            .count()
+    };
+    let random_wins = wins(BotLevel::Random);
+    assert!(wins(BotLevel::Greedy) > random_wins);
+    assert!(wins(BotLevel::Perfect) > random_wins);
+}
@@ -0,0 +1,29 @@
+//! Fixtures shared by the tests.
+
+// Each test crate uses only some of them
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::PathBuf;
+
+use sui_types::crypto::{random_key_pair_by_type, SignatureScheme, SuiKeyPair};
+
+/// The key schemes a participant of a multisig can have
+pub const SCHEMES: [SignatureScheme; 3] = [
+    SignatureScheme::ED25519,
+    SignatureScheme::Secp256k1,
+    SignatureScheme::Secp256r1,
+];
+
+pub fn new_key_pair(scheme: SignatureScheme) -> SuiKeyPair {
+    random_key_pair_by_type(scheme).unwrap().1
+}
+
+/// Game store in a directory of its own, which does not exist yet
+pub fn store_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join(format!("multisig-tic-tac-toe-{}", std::process::id()))
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    dir.join("games.json")
+}
@@ -0,0 +1,170 @@
+//! Decodes golden BCS bytes of the objects of the contract, laid out as in
+//! `multisig_tic_tac_toe.move`.
+
+use move_core_types::language_storage::StructTag;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::gas_coin::GasCoin;
+use sui_types::id::{ID, UID};
+
+use rust_cli_client::contract_structs::{
+    decode_move_struct, Mark, MoveStruct, TicTacToe, TicTacToeTrophy,
+};
+use rust_cli_client::error::TicTacToeError;
+
+const PACKAGE_ID: ObjectID = ObjectID::new([0x0a; 32]);
+const OBJECT_ID: ObjectID = ObjectID::new([0x11; 32]);
+const OTHER_ID: ObjectID = ObjectID::new([0x22; 32]);
+
+fn address(byte: u8) -> SuiAddress {
+    SuiAddress::from(ObjectID::new([byte; 32]))
+}
+
+fn decode<T: MoveStruct>(bytes: &[u8]) -> Result<T, TicTacToeError> {
+    decode_move_struct(PACKAGE_ID, &T::struct_tag(PACKAGE_ID), bytes)
+}
+
+fn tic_tac_toe_bytes() -> Vec<u8> {
+    [
+        // id
+        &[0x11; 32][..],
+        // gameboard, length first
+        &[9, 1, 0, 0, 2, 1, 0, 0, 0, 2],
+        // cur_turn
+        &[4],
+        // x_addr
+        &[0xaa; 32],
+        // o_addr
+        &[0xbb; 32],
+        // finished
+        &[0],
+    ]
+    .concat()
+}
+
+#[test]
+fn test_decode_tic_tac_toe() {
+    let game: TicTacToe = decode(&tic_tac_toe_bytes()).unwrap();
+    assert_eq!(
+        game,
+        TicTacToe {
+            id: UID {
+                id: ID { bytes: OBJECT_ID },
+            },
+            gameboard: vec![1, 0, 0, 2, 1, 0, 0, 0, 2],
+            cur_turn: 4,
+            x_addr: address(0xaa),
+            o_addr: address(0xbb),
+            finished: 0,
+        }
+    );
+}
+
+#[test]
+fn test_decode_mark() {
+    let bytes = [
+        // id
+        &[0x11; 32][..],
+        // placement, Some(4)
+        &[1, 4],
+        // during_turn
+        &[0],
+        // game_owners
+        &[0xcc; 32],
+        // game_id
+        &[0x22; 32],
+    ]
+    .concat();
+    let mark: Mark = decode(&bytes).unwrap();
+    assert_eq!(
+        mark,
+        Mark {
+            id: UID {
+                id: ID { bytes: OBJECT_ID },
+            },
+            placement: Some(4),
+            during_turn: false,
+            game_owners: address(0xcc),
+            game_id: ID { bytes: OTHER_ID },
+        }
+    );
+
+    // Placement is emptied when the mark is placed
+    let bytes = [&[0x11; 32][..], &[0], &[1], &[0xcc; 32], &[0x22; 32]].concat();
+    let mark: Mark = decode(&bytes).unwrap();
+    assert_eq!(mark.placement, None);
+    assert!(mark.during_turn);
+}
+
+#[test]
+fn test_decode_trophy() {
+    let bytes = [
+        // id
+        &[0x11; 32][..],
+        // winner
+        &[0xaa; 32],
+        // loser
+        &[0xbb; 32],
+        // played_as
+        &[2],
+        // game_id
+        &[0x22; 32],
+    ]
+    .concat();
+    let trophy: TicTacToeTrophy = decode(&bytes).unwrap();
+    assert_eq!(
+        trophy,
+        TicTacToeTrophy {
+            id: OBJECT_ID,
+            winner: address(0xaa),
+            loser: address(0xbb),
+            played_as: 2,
+            game_id: OTHER_ID,
+        }
+    );
+}
+
+#[test]
+fn test_struct_tags() {
+    let tag = TicTacToe::struct_tag(PACKAGE_ID);
+    assert_eq!(tag.address, PACKAGE_ID.into());
+    assert_eq!(tag.module.as_str(), "multisig_tic_tac_toe");
+    assert_eq!(tag.name.as_str(), "TicTacToe");
+    assert_eq!(Mark::struct_tag(PACKAGE_ID).name.as_str(), "Mark");
+    assert_eq!(
+        TicTacToeTrophy::struct_tag(PACKAGE_ID).name.as_str(),
+        "TicTacToeTrophy"
+    );
+    // Gas coins do not belong to the package
+    assert_eq!(GasCoin::struct_tag(PACKAGE_ID), GasCoin::type_());
+}
+
+#[test]
+fn test_wrong_type() {
+    // A mark is not a game
+    let res: Result<TicTacToe, _> = decode_move_struct(
+        PACKAGE_ID,
+        &Mark::struct_tag(PACKAGE_ID),
+        &tic_tac_toe_bytes(),
+    );
+    assert!(matches!(res, Err(TicTacToeError::UnexpectedType { .. })));
+
+    // Nor is a game of another package
+    let other_package: StructTag = TicTacToe::struct_tag(OTHER_ID);
+    let res: Result<TicTacToe, _> =
+        decode_move_struct(PACKAGE_ID, &other_package, &tic_tac_toe_bytes());
+    assert!(matches!(res, Err(TicTacToeError::UnexpectedType { .. })));
+}
+
+#[test]
+fn test_invalid_layout() {
+    let mut bytes = tic_tac_toe_bytes();
+    bytes.pop();
+    let res: Result<TicTacToe, _> = decode(&bytes);
+    assert!(matches!(
+        res,
+        Err(TicTacToeError::InvalidLayout {
+            struct_name: "TicTacToe",
+            ..
+        })
+    ));
+}
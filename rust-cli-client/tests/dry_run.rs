@@ -0,0 +1,42 @@
+//! Estimates the gas budget of transactions out of their dry run.
+
+use sui_types::gas::GasCostSummary;
+
+use rust_cli_client::dry_run::{DryRunMode, GasEstimate, DEFAULT_GAS_MARGIN_PERCENT};
+
+const GAS_PRICE: u64 = 750;
+
+#[test]
+fn test_budget_with_margin() {
+    let summary = GasCostSummary::new(1_000_000, 2_000_000, 500_000, 5_000);
+    let estimate = GasEstimate::new(&summary, GAS_PRICE, 20);
+    // The rebate is only paid back after the transaction
+    assert_eq!(estimate.budget, 3_600_000);
+    assert_eq!(estimate.net_cost(), 2_500_000);
+
+    let estimate = GasEstimate::new(&summary, GAS_PRICE, 0);
+    assert_eq!(estimate.budget, 3_000_000);
+}
+
+#[test]
+fn test_budget_at_least_gas_price() {
+    let summary = GasCostSummary::new(0, 0, 1_000, 0);
+    let estimate = GasEstimate::new(&summary, GAS_PRICE, 20);
+    assert_eq!(estimate.budget, GAS_PRICE);
+    // Deleting objects can pay
+    assert_eq!(estimate.net_cost(), -1_000);
+}
+
+#[test]
+fn test_modes() {
+    assert!(!DryRunMode::default().is_enabled());
+    assert!(DryRunMode::Only.is_enabled());
+    assert_eq!(
+        DryRunMode::Only.margin_percent(),
+        DEFAULT_GAS_MARGIN_PERCENT
+    );
+    assert_eq!(
+        DryRunMode::Estimate { margin_percent: 50 }.margin_percent(),
+        50
+    );
+}
@@ -0,0 +1,264 @@
+//! Replays the scenarios of `contract/tests/test_multisig_tic_tac_toe.move` on the Rust game engine.
+
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::id::{ID, UID};
+
+use rust_cli_client::contract_structs::{Mark, TicTacToe};
+use rust_cli_client::error::AbortCode;
+use rust_cli_client::game_engine::{
+    delete_game, get_index, place_mark, send_mark_to_game, Board, EngineError, GameStatus,
+    PlaceOutcome, MARK_EMPTY, MARK_O, MARK_X,
+};
+use rust_cli_client::startup::Player;
+
+fn create_game() -> (TicTacToe, Mark) {
+    let multisig_addr = SuiAddress::random_for_testing_only();
+    let game_id = ObjectID::random();
+    let game = TicTacToe {
+        id: UID { id: ID { bytes: game_id } },
+        gameboard: vec![MARK_EMPTY; 9],
+        cur_turn: 0,
+        x_addr: SuiAddress::random_for_testing_only(),
+        o_addr: SuiAddress::random_for_testing_only(),
+        finished: 0,
+    };
+    let mark = Mark {
+        id: UID { id: ID { bytes: ObjectID::random() } },
+        placement: None,
+        during_turn: true,
+        game_owners: multisig_addr,
+        game_id: ID { bytes: game_id },
+    };
+    (game, mark)
+}
+
+fn legit_mark(placement: u8, game: &TicTacToe) -> Mark {
+    Mark {
+        id: UID { id: ID { bytes: ObjectID::random() } },
+        placement: Some(placement),
+        during_turn: false,
+        game_owners: SuiAddress::random_for_testing_only(),
+        game_id: ID { bytes: game.id.id.bytes },
+    }
+}
+
+/// Places the marks in order and returns the outcome of the last one
+fn play(game: &mut TicTacToe, placements: &[u8]) -> PlaceOutcome {
+    let mut outcome = None;
+    for &placement in placements {
+        outcome = Some(place_mark(game, &mut legit_mark(placement, game)).unwrap());
+    }
+    outcome.unwrap()
+}
+
+#[test]
+fn test_create_game() {
+    let (game, mark) = create_game();
+    let board = Board::from(&game);
+    assert_eq!(board, Board::new());
+    assert_eq!(board.cur_player(), Player::X);
+    assert_eq!(board.status().unwrap(), GameStatus::InProgress);
+    assert!(mark.during_turn);
+    assert_eq!(mark.game_id.bytes, game.id.id.bytes);
+}
+
+#[test]
+fn test_send_mark() {
+    let (_, mut mark) = create_game();
+    send_mark_to_game(&mut mark, 0, 0).unwrap();
+    assert_eq!(mark.placement, Some(0));
+    assert!(!mark.during_turn);
+}
+
+#[test]
+fn test_invalid_col_placement() {
+    let (_, mut mark) = create_game();
+    let err = send_mark_to_game(&mut mark, 0, 4).unwrap_err();
+    assert!(matches!(
+        err,
+        EngineError::Abort {
+            code: AbortCode::EInvalidSize,
+            ..
+        }
+    ));
+    assert!(get_index(0, 4).is_err());
+    assert!(!Board::new().is_legal_move(0, 4));
+}
+
+#[test]
+fn test_invalid_row_placement() {
+    let (_, mut mark) = create_game();
+    let err = send_mark_to_game(&mut mark, 255, 0).unwrap_err();
+    assert!(matches!(
+        err,
+        EngineError::Abort {
+            code: AbortCode::EInvalidSize,
+            ..
+        }
+    ));
+    assert!(!Board::new().is_legal_move(255, 0));
+}
+
+#[test]
+fn test_cheat() {
+    let (_, mut mark) = create_game();
+    send_mark_to_game(&mut mark, 0, 1).unwrap();
+    let err = send_mark_to_game(&mut mark, 1, 1).unwrap_err();
+    assert!(matches!(
+        err,
+        EngineError::Abort {
+            code: AbortCode::ETriedToCheat,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_place_mark() {
+    let (mut game, mut mark) = create_game();
+    send_mark_to_game(&mut mark, 0, 1).unwrap();
+    let outcome = place_mark(&mut game, &mut mark).unwrap();
+    assert_eq!(outcome, PlaceOutcome::NextTurn(Player::O));
+    assert_eq!(game.gameboard[3], MARK_X);
+    assert_eq!(game.cur_turn, 1);
+    assert!(mark.during_turn);
+    assert_eq!(mark.placement, None);
+}
+
+#[test]
+fn test_invalid_mark() {
+    let (mut game, _) = create_game();
+    let (_, mut fake_mark) = create_game();
+    fake_mark.placement = Some(2);
+    fake_mark.during_turn = false;
+    let err = place_mark(&mut game, &mut fake_mark).unwrap_err();
+    assert!(matches!(
+        err,
+        EngineError::Abort {
+            code: AbortCode::EMarkIsFromDifferentGame,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_cell_already_set() {
+    let (mut game, mut mark) = create_game();
+    let (row, col) = (0, 1);
+
+    send_mark_to_game(&mut mark, row, col).unwrap();
+    assert_eq!(
+        place_mark(&mut game, &mut mark).unwrap(),
+        PlaceOutcome::NextTurn(Player::O)
+    );
+
+    assert!(!Board::from(&game).is_legal_move(row, col));
+    send_mark_to_game(&mut mark, row, col).unwrap();
+    assert_eq!(
+        place_mark(&mut game, &mut mark).unwrap(),
+        PlaceOutcome::Rejected
+    );
+    // Mark is passed back to O, without changing the turn
+    assert!(mark.during_turn);
+    assert_eq!(game.cur_turn, 1);
+    assert_eq!(Board::from(&game).cur_player(), Player::O);
+}
+
+#[test]
+fn test_diag_x_win() {
+    let (mut game, _) = create_game();
+    //  0 | 3 | 6     X | O | O
+    // -----------    ----------
+    //  1 | 4 | 7  ->   | X |
+    // -----------    ----------
+    //  2 | 5 | 8       |   | X
+    assert_eq!(play(&mut game, &[0, 3, 4, 6, 8]), PlaceOutcome::Won(Player::X));
+    assert_eq!(game.finished, 1);
+    assert_eq!(
+        Board::from(&game).status().unwrap(),
+        GameStatus::Won(Player::X)
+    );
+}
+
+#[test]
+fn test_diag_o_win() {
+    let (mut game, _) = create_game();
+    //  0 | 3 | 6     X | X | O
+    // -----------    ----------
+    //  1 | 4 | 7  ->   | O |
+    // -----------    ----------
+    //  2 | 5 | 8     O |   | X
+    assert_eq!(
+        play(&mut game, &[0, 2, 3, 6, 8, 4]),
+        PlaceOutcome::Won(Player::O)
+    );
+    assert_eq!(game.finished, 2);
+    assert_eq!(game.gameboard[4], MARK_O);
+}
+
+#[test]
+fn test_winning_line() {
+    let (mut game, _) = create_game();
+    //  0 | 3 | 6     X | O |
+    // -----------    ----------
+    //  1 | 4 | 7  -> X | O |
+    // -----------    ----------
+    //  2 | 5 | 8     X |   |
+    assert_eq!(
+        play(&mut game, &[0, 3, 1]),
+        PlaceOutcome::NextTurn(Player::O)
+    );
+    assert_eq!(Board::from(&game).winning_line(), None);
+    assert_eq!(play(&mut game, &[4, 2]), PlaceOutcome::Won(Player::X));
+    assert_eq!(Board::from(&game).winning_line(), Some([0, 1, 2]));
+
+    // Diagonals are checked last
+    let (mut game, _) = create_game();
+    play(&mut game, &[0, 3, 4, 6, 8]);
+    assert_eq!(Board::from(&game).winning_line(), Some([0, 4, 8]));
+}
+
+#[test]
+fn test_draw() {
+    let (mut game, _) = create_game();
+    //  0 | 3 | 6     X | O | O
+    // -----------    ----------
+    //  1 | 4 | 7  -> O | X | X
+    // -----------    ----------
+    //  2 | 5 | 8     X | X | O
+    assert_eq!(
+        play(&mut game, &[0, 1, 2, 3, 4, 6, 5, 8, 7]),
+        PlaceOutcome::Draw
+    );
+    assert_eq!(game.finished, 3);
+    assert_eq!(Board::from(&game).status().unwrap(), GameStatus::Draw);
+}
+
+#[test]
+fn test_illegal_delete() {
+    let (mut game, _) = create_game();
+    let err = delete_game(&game).unwrap_err();
+    assert!(matches!(
+        err,
+        EngineError::Abort {
+            code: AbortCode::ETriedToCheat,
+            ..
+        }
+    ));
+
+    play(&mut game, &[0, 3, 4, 6, 8]);
+    delete_game(&game).unwrap();
+}
+
+#[test]
+fn test_invalid_size() {
+    // Boards decoded from elsewhere may not be 3x3
+    let mut board = Board {
+        gameboard: vec![MARK_X; 3],
+        cur_turn: 4,
+        finished: 0,
+    };
+    assert_eq!(board.get_winner(), None);
+    assert_eq!(board.winning_line(), None);
+    assert!(board.place_mark(0).is_err());
+}
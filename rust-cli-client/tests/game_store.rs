@@ -0,0 +1,153 @@
+//! Records games and pending transactions across restarts of the CLI.
+
+use std::fs;
+
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest};
+
+use rust_cli_client::game_backend::GameInfo;
+use rust_cli_client::game_store::{GameStore, MoveRecord};
+use rust_cli_client::network_config::Network;
+
+mod common;
+use common::store_path;
+
+fn game_info() -> GameInfo {
+    GameInfo {
+        game_id: ObjectID::random(),
+        mark_id: ObjectID::random(),
+    }
+}
+
+#[test]
+fn test_missing_store_is_empty() {
+    let store = GameStore::load(&store_path("missing"), Network::Testnet).unwrap();
+    assert!(store
+        .account(SuiAddress::random_for_testing_only())
+        .is_none());
+}
+
+#[test]
+fn test_round_trip() {
+    let path = store_path("round_trip");
+    let multisig = SuiAddress::random_for_testing_only();
+    let game_info = game_info();
+    let digest = TransactionDigest::random();
+
+    let mut store = GameStore::load(&path, Network::Testnet).unwrap();
+    let record = store.record_game(multisig, &game_info);
+    record.last_seen_version = Some(SequenceNumber::from_u64(7));
+    record.moves.push(MoveRecord {
+        turn: 0,
+        row: 1,
+        col: 2,
+        rejected: false,
+    });
+    store.add_pending(multisig, digest);
+    store.save().unwrap();
+
+    let store = GameStore::load(&path, Network::Testnet).unwrap();
+    let record = store.game(multisig, game_info.game_id).unwrap();
+    assert_eq!(record.mark_id, game_info.mark_id);
+    assert_eq!(record.last_seen_version, Some(SequenceNumber::from_u64(7)));
+    assert_eq!(record.moves.len(), 1);
+    assert_eq!(store.account(multisig).unwrap().pending, vec![digest]);
+    // Nothing is left half written
+    assert!(!path.with_extension("json.tmp").exists());
+}
+
+#[test]
+fn test_games_by_account() {
+    let mut store = GameStore::load(&store_path("by_account"), Network::Testnet).unwrap();
+    let multisig = SuiAddress::random_for_testing_only();
+    let other_multisig = SuiAddress::random_for_testing_only();
+    let game_info = game_info();
+
+    store
+        .record_game(multisig, &game_info)
+        .moves
+        .push(MoveRecord {
+            turn: 0,
+            row: 0,
+            col: 0,
+            rejected: false,
+        });
+    // Recording a game again keeps its record
+    store.record_game(multisig, &game_info);
+    assert_eq!(store.account(multisig).unwrap().games.len(), 1);
+    assert_eq!(
+        store.game(multisig, game_info.game_id).unwrap().moves.len(),
+        1
+    );
+    assert!(store.game(other_multisig, game_info.game_id).is_none());
+
+    store.remove_game(multisig, game_info.game_id);
+    assert!(store.game(multisig, game_info.game_id).is_none());
+}
+
+#[test]
+fn test_games_by_network() {
+    let path = store_path("by_network");
+    let multisig = SuiAddress::random_for_testing_only();
+    let game_info = game_info();
+    let digest = TransactionDigest::random();
+
+    let mut store = GameStore::load(&path, Network::Testnet).unwrap();
+    store.record_game(multisig, &game_info);
+    store.add_pending(multisig, digest);
+    store.save().unwrap();
+
+    // The same account on another network has other games and transactions
+    let mut store = GameStore::load(&path, Network::Devnet).unwrap();
+    assert!(store.game(multisig, game_info.game_id).is_none());
+    assert!(store.take_pending(multisig).is_empty());
+    store.record_game(multisig, &game_info);
+    store.remove_game(multisig, game_info.game_id);
+    store.save().unwrap();
+
+    let mut store = GameStore::load(&path, Network::Testnet).unwrap();
+    assert!(store.game(multisig, game_info.game_id).is_some());
+    assert_eq!(store.take_pending(multisig), vec![digest]);
+}
+
+#[test]
+fn test_resigned() {
+    let path = store_path("resigned");
+    let multisig = SuiAddress::random_for_testing_only();
+    let (resigned, played) = (game_info(), game_info());
+
+    // Stores written before games could be resigned have no such field
+    let mut store = GameStore::load(&path, Network::Testnet).unwrap();
+    store.record_game(multisig, &played);
+    store.save().unwrap();
+    let mut json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    let record = json["testnet"][multisig.to_string()]["games"][0]
+        .as_object_mut()
+        .unwrap();
+    assert!(record.remove("resigned").is_some());
+    fs::write(&path, json.to_string()).unwrap();
+
+    let mut store = GameStore::load(&path, Network::Testnet).unwrap();
+    assert!(store.game(multisig, played.game_id).is_some());
+    assert!(!store.is_resigned(multisig, played.game_id));
+    store.record_game(multisig, &resigned).resigned = true;
+    store.save().unwrap();
+
+    let store = GameStore::load(&path, Network::Testnet).unwrap();
+    assert!(store.is_resigned(multisig, resigned.game_id));
+    assert!(!store.is_resigned(multisig, played.game_id));
+}
+
+#[test]
+fn test_take_pending() {
+    let mut store = GameStore::load(&store_path("pending"), Network::Testnet).unwrap();
+    let multisig = SuiAddress::random_for_testing_only();
+    let (settled, pending) = (TransactionDigest::random(), TransactionDigest::random());
+
+    store.add_pending(multisig, settled);
+    store.add_pending(multisig, pending);
+    store.add_pending(multisig, pending);
+    store.remove_pending(multisig, settled);
+    assert_eq!(store.take_pending(multisig), vec![pending]);
+    assert!(store.take_pending(multisig).is_empty());
+}
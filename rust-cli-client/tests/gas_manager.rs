@@ -0,0 +1,154 @@
+//! Plans the gas coins of concurrent transactions.
+
+use sui_types::base_types::{random_object_ref, ObjectID, SuiAddress};
+
+use rust_cli_client::gas_manager::{
+    GasCoinInfo, GasKey, GasManager, GasPlan, DEDICATED_COIN_TXS, MIN_DUST_TO_MERGE,
+};
+
+const BUDGET: u64 = 1_000;
+
+fn coin(balance: u64) -> GasCoinInfo {
+    GasCoinInfo {
+        object_ref: random_object_ref(),
+        balance,
+    }
+}
+
+fn game_key() -> GasKey {
+    GasKey {
+        owner: SuiAddress::random_for_testing_only(),
+        game_object: ObjectID::random(),
+    }
+}
+
+#[test]
+fn test_best_fit() {
+    let manager = GasManager::new();
+    let coins = [coin(BUDGET * 5), coin(BUDGET / 2), coin(BUDGET * 2)];
+    assert_eq!(
+        manager.plan(None, &coins, BUDGET, &[]),
+        Some(GasPlan::Use(coins[2].object_ref))
+    );
+    assert_eq!(manager.plan(None, &coins[1..2], BUDGET, &[]), None);
+}
+
+#[test]
+fn test_reserved_and_excluded() {
+    let manager = GasManager::new();
+    let coins = [coin(BUDGET), coin(BUDGET * 2)];
+
+    let reservation = manager.reserve(coins[0].object_ref, None).unwrap();
+    assert!(manager.reserve(coins[0].object_ref, None).is_none());
+    assert_eq!(
+        manager.plan(None, &coins, BUDGET, &[]),
+        Some(GasPlan::Use(coins[1].object_ref))
+    );
+    // Locked coins are excluded by the caller
+    assert_eq!(manager.plan(None, &coins, BUDGET, &[coins[1].id()]), None);
+
+    drop(reservation);
+    assert_eq!(
+        manager.plan(None, &coins, BUDGET, &[coins[1].id()]),
+        Some(GasPlan::Use(coins[0].object_ref))
+    );
+}
+
+#[test]
+fn test_dedicated_coin() {
+    let manager = GasManager::new();
+    let (game, other_game) = (game_key(), game_key());
+    let coins = [coin(BUDGET), coin(BUDGET * 2)];
+
+    drop(manager.reserve(coins[1].object_ref, Some(game)));
+    // The game keeps paying with its coin, which the other games leave alone
+    assert_eq!(
+        manager.plan(Some(game), &coins, BUDGET, &[]),
+        Some(GasPlan::Use(coins[1].object_ref))
+    );
+    assert_eq!(
+        manager.plan(Some(other_game), &coins, BUDGET, &[]),
+        Some(GasPlan::Use(coins[0].object_ref))
+    );
+    assert_eq!(manager.plan(None, &coins[1..], BUDGET, &[]), None);
+
+    // Once spent, another coin is dedicated to the game
+    let coins = [coins[0], coin(BUDGET / 2)];
+    assert_eq!(
+        manager.plan(Some(game), &coins, BUDGET, &[]),
+        Some(GasPlan::Use(coins[0].object_ref))
+    );
+}
+
+#[test]
+fn test_split_only_coin() {
+    let manager = GasManager::new();
+    let split_amount = BUDGET * DEDICATED_COIN_TXS;
+    let coins = [coin(split_amount * 3)];
+    assert_eq!(
+        manager.plan(Some(game_key()), &coins, BUDGET, &[]),
+        Some(GasPlan::Split {
+            from: coins[0].object_ref,
+            amount: split_amount,
+        })
+    );
+    // Transactions of no game pay with it
+    assert_eq!(
+        manager.plan(None, &coins, BUDGET, &[]),
+        Some(GasPlan::Use(coins[0].object_ref))
+    );
+    // Too small to split
+    let coins = [coin(split_amount)];
+    assert_eq!(
+        manager.plan(Some(game_key()), &coins, BUDGET, &[]),
+        Some(GasPlan::Use(coins[0].object_ref))
+    );
+}
+
+#[test]
+fn test_merge_dust() {
+    let manager = GasManager::new();
+    let mut coins = vec![coin(BUDGET * 3)];
+    coins.extend((0..MIN_DUST_TO_MERGE).map(|_| coin(BUDGET / 10)));
+
+    let merge = manager.merge_plan(&coins, BUDGET, &[]).unwrap();
+    assert_eq!(merge.primary, coins[0].object_ref);
+    assert_eq!(
+        merge.dust,
+        coins[1..]
+            .iter()
+            .map(|coin| coin.object_ref)
+            .collect::<Vec<_>>()
+    );
+
+    // Not enough dust
+    assert_eq!(
+        manager.merge_plan(&coins[..MIN_DUST_TO_MERGE], BUDGET, &[]),
+        None
+    );
+    // Nor enough gas to pay for the merge
+    assert_eq!(manager.merge_plan(&coins[1..], BUDGET, &[]), None);
+}
+
+#[test]
+fn test_merge_without_locked_coins() {
+    let manager = GasManager::new();
+    let mut coins = vec![coin(BUDGET * 3), coin(BUDGET * 2)];
+    coins.extend((0..=MIN_DUST_TO_MERGE).map(|_| coin(BUDGET / 10)));
+
+    // A locked coin would lock the merge too, so it is left out, as primary or as dust
+    let locked = [coins[0].id(), coins[2].id()];
+    let merge = manager.merge_plan(&coins, BUDGET, &locked).unwrap();
+    assert_eq!(merge.primary, coins[1].object_ref);
+    assert_eq!(
+        merge.dust,
+        coins[3..]
+            .iter()
+            .map(|coin| coin.object_ref)
+            .collect::<Vec<_>>()
+    );
+
+    // Without it, there is not enough dust left
+    let locked = [coins[2].id(), coins[3].id()];
+    assert_eq!(manager.merge_plan(&coins, BUDGET, &locked), None);
+}
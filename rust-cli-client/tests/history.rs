@@ -0,0 +1,107 @@
+//! Rebuilds the moves of a game from the transactions of its mark, as the indexer returns them.
+
+use sui_types::base_types::{SequenceNumber, SuiAddress, TransactionDigest};
+
+use rust_cli_client::game_engine::{PlaceOutcome, MARK_EMPTY, MARK_O, MARK_X};
+use rust_cli_client::history::{rebuild_history, MarkCall, MarkTransaction};
+use rust_cli_client::startup::Player;
+
+/// Transactions of the moves, the mark being at version 1 when the game is created
+fn mark_transactions(moves: &[(u8, u8, SuiAddress)]) -> Vec<MarkTransaction> {
+    let mut version = 0;
+    let mut tx = |sender, call| {
+        version += 1;
+        MarkTransaction {
+            digest: TransactionDigest::random(),
+            sender,
+            timestamp_ms: Some(1_700_000_000_000 + version),
+            mark_version: SequenceNumber::from_u64(version),
+            call,
+        }
+    };
+    let multisig = SuiAddress::random_for_testing_only();
+    moves
+        .iter()
+        .flat_map(|&(row, col, sender)| {
+            [
+                tx(sender, MarkCall::Send { row, col }),
+                tx(multisig, MarkCall::Place),
+            ]
+        })
+        .collect()
+}
+
+#[test]
+fn test_rebuild_history() {
+    let (x_addr, o_addr) = (
+        SuiAddress::random_for_testing_only(),
+        SuiAddress::random_for_testing_only(),
+    );
+    let mut txs = mark_transactions(&[(0, 0, x_addr), (1, 1, o_addr), (0, 1, x_addr)]);
+    let placed = txs[3].digest;
+    // Transactions are ordered by the version of the mark, not by the order they are queried in
+    txs.reverse();
+
+    let moves = rebuild_history(txs).unwrap();
+    assert_eq!(moves.len(), 3);
+    assert_eq!(
+        (moves[1].turn, moves[1].player, moves[1].row, moves[1].col),
+        (1, Player::O, 1, 1)
+    );
+    assert_eq!(moves[1].signer, o_addr);
+    assert_eq!(moves[1].digest, placed);
+    assert_eq!(moves[1].outcome, PlaceOutcome::NextTurn(Player::X));
+    assert_eq!(
+        moves[2].board.gameboard,
+        vec![
+            MARK_X, MARK_EMPTY, MARK_EMPTY, MARK_X, MARK_O, MARK_EMPTY, MARK_EMPTY, MARK_EMPTY,
+            MARK_EMPTY
+        ]
+    );
+}
+
+#[test]
+fn test_rejected_move() {
+    let (x_addr, o_addr) = (
+        SuiAddress::random_for_testing_only(),
+        SuiAddress::random_for_testing_only(),
+    );
+    // O tries the cell of X, and the mark bounces back to them
+    let txs = mark_transactions(&[(0, 0, x_addr), (0, 0, o_addr), (2, 2, o_addr)]);
+
+    let moves = rebuild_history(txs).unwrap();
+    assert!(moves[1].is_rejected());
+    assert_eq!(moves[1].turn, 1);
+    assert_eq!(moves[1].board, moves[0].board);
+    assert_eq!((moves[2].turn, moves[2].player), (1, Player::O));
+    assert!(!moves[2].is_rejected());
+}
+
+#[test]
+fn test_winning_and_pending_moves() {
+    let (x_addr, o_addr) = (
+        SuiAddress::random_for_testing_only(),
+        SuiAddress::random_for_testing_only(),
+    );
+    let mut txs = mark_transactions(&[
+        (0, 0, x_addr),
+        (0, 1, o_addr),
+        (1, 0, x_addr),
+        (1, 1, o_addr),
+        (2, 0, x_addr),
+    ]);
+    let moves = rebuild_history(txs.clone()).unwrap();
+    assert_eq!(moves[4].outcome, PlaceOutcome::Won(Player::X));
+    assert_eq!(moves[4].board.finished, 1);
+
+    // A mark sent but not placed yet is no move
+    txs.truncate(txs.len() - 1);
+    assert_eq!(rebuild_history(txs).unwrap().len(), 4);
+}
+
+#[test]
+fn test_placed_without_being_sent() {
+    let mut txs = mark_transactions(&[(0, 0, SuiAddress::random_for_testing_only())]);
+    txs.remove(0);
+    assert!(rebuild_history(txs).is_err());
+}
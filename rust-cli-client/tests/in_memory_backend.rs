@@ -0,0 +1,663 @@
+//! Plays whole games through the play loop of the CLI, against the in-memory backend.
+
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::timeout;
+
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::crypto::SignatureScheme;
+
+use rust_cli_client::error::{AbortCode, TicTacToeError};
+use rust_cli_client::game_backend::{GameBackend, GameInfo, MoveOutcome};
+use rust_cli_client::game_engine::{Board, PlaceOutcome};
+use rust_cli_client::game_loop::{find_game_info, play_game, resume_game_info, MoveInput};
+use rust_cli_client::game_store::GameStore;
+use rust_cli_client::in_memory_backend::InMemoryBackend;
+use rust_cli_client::multi_sig_1_out_of_n::MultiSig1OutOfN;
+use rust_cli_client::network_config::Network;
+use rust_cli_client::notation::GameNotation;
+use rust_cli_client::row_col::{Col, Row, RowCol};
+use rust_cli_client::startup::Config;
+use rust_cli_client::startup::Player;
+use rust_cli_client::turn_watcher::PollConfig;
+
+mod common;
+use common::{new_key_pair, store_path, SCHEMES};
+
+fn poll_config() -> PollConfig {
+    PollConfig {
+        initial_interval: Duration::from_millis(1),
+        max_interval: Duration::from_millis(10),
+        multiplier: 2,
+    }
+}
+
+/// Moves as (row, col)
+struct ScriptedInput(VecDeque<(u8, u8)>);
+
+impl MoveInput for ScriptedInput {
+    fn next_move(&mut self, _gameboard: &[u8]) -> anyhow::Result<(Row, Col)> {
+        let (row, col) = self.0.pop_front().expect("Ran out of scripted moves");
+        Ok((
+            RowCol::try_from(row).unwrap(),
+            RowCol::try_from(col).unwrap(),
+        ))
+    }
+}
+
+/// Configs of player X and player O, with Ed25519 keys
+fn configs() -> (Config, Config) {
+    configs_with_schemes(SignatureScheme::ED25519, SignatureScheme::ED25519)
+}
+
+fn configs_with_schemes(x_scheme: SignatureScheme, o_scheme: SignatureScheme) -> (Config, Config) {
+    let x_signer = new_key_pair(x_scheme);
+    let o_signer = new_key_pair(o_scheme);
+    let multisig = MultiSig1OutOfN::new(vec![x_signer.public(), o_signer.public()]).unwrap();
+    (
+        Config {
+            signer: x_signer,
+            multisig: multisig.clone(),
+        },
+        Config {
+            signer: o_signer,
+            multisig,
+        },
+    )
+}
+
+/// Creates a game as the `new` command does
+async fn new_game(backend: &InMemoryBackend, config: &Config) -> GameInfo {
+    backend
+        .create_game(&config.signer, &config.multisig)
+        .await
+        .unwrap()
+}
+
+/// Looks up the objects of a game as the `join` command does
+async fn join_game(backend: &InMemoryBackend, config: &Config, game_id: ObjectID) -> GameInfo {
+    let game = backend.fetch_game(game_id).await.unwrap();
+    find_game_info(backend, config, &game).await.unwrap()
+}
+
+async fn play(
+    backend: &InMemoryBackend,
+    x_moves: &[(u8, u8)],
+    o_moves: &[(u8, u8)],
+) -> (Config, Config) {
+    play_with_configs(backend, configs(), x_moves, o_moves).await
+}
+
+async fn play_with_configs(
+    backend: &InMemoryBackend,
+    (x_config, o_config): (Config, Config),
+    x_moves: &[(u8, u8)],
+    o_moves: &[(u8, u8)],
+) -> (Config, Config) {
+    let x_game = new_game(backend, &x_config).await;
+    let o_game = join_game(backend, &o_config, x_game.game_id).await;
+    assert_eq!(x_game.game_id, o_game.game_id);
+    assert_eq!(x_game.mark_id, o_game.mark_id);
+
+    let mut x_input = ScriptedInput(x_moves.iter().copied().collect());
+    let mut o_input = ScriptedInput(o_moves.iter().copied().collect());
+    let (x_res, o_res) = tokio::join!(
+        play_game(
+            backend,
+            &x_config,
+            x_game,
+            &mut x_input,
+            poll_config(),
+            None
+        ),
+        play_game(
+            backend,
+            &o_config,
+            o_game,
+            &mut o_input,
+            poll_config(),
+            None
+        ),
+    );
+    x_res.unwrap();
+    o_res.unwrap();
+    assert!(x_input.0.is_empty() && o_input.0.is_empty());
+    (x_config, o_config)
+}
+
+#[tokio::test]
+async fn test_create_game_ownership() {
+    let backend = InMemoryBackend::new();
+    let (x_config, o_config) = configs();
+    let game_info = new_game(&backend, &x_config).await;
+
+    let multisig_addr = x_config.multisig.admin_address();
+    let x_addr = SuiAddress::from(&x_config.signer.public());
+    assert_eq!(backend.owner(game_info.game_id), Some(multisig_addr));
+    assert_eq!(backend.owner(game_info.mark_id), Some(x_addr));
+
+    // O cannot send a mark it does not own
+    assert!(backend
+        .send_mark_to_game(&o_config.signer, game_info.mark_id, RowCol::First, RowCol::First)
+        .await
+        .is_err());
+
+    backend
+        .send_mark_to_game(&x_config.signer, game_info.mark_id, RowCol::First, RowCol::First)
+        .await
+        .unwrap();
+    assert_eq!(backend.owner(game_info.mark_id), Some(multisig_addr));
+
+    // Only participants of the multisig can place the mark
+    let (outsider, _) = configs();
+    assert!(backend
+        .place_mark(
+            &outsider.signer,
+            &x_config.multisig,
+            game_info.game_id,
+            game_info.mark_id
+        )
+        .await
+        .is_err());
+
+    // Any of the participants can place it
+    let outcome = backend
+        .place_mark(
+            &o_config.signer,
+            &x_config.multisig,
+            game_info.game_id,
+            game_info.mark_id,
+        )
+        .await
+        .unwrap();
+    assert_eq!(outcome, PlaceOutcome::NextTurn(Player::O));
+    let o_addr = SuiAddress::from(&o_config.signer.public());
+    assert_eq!(backend.owner(game_info.mark_id), Some(o_addr));
+    let game = backend.fetch_game(game_info.game_id).await.unwrap();
+    assert_eq!(game.gameboard[0], 1);
+    assert_eq!(game.cur_turn, 1);
+
+    // The game is played by the participants of the multisig only
+    assert!(x_config.multisig.plays(&game));
+    assert!(!outsider.multisig.plays(&game));
+}
+
+#[tokio::test]
+async fn test_not_found_errors() {
+    let backend = InMemoryBackend::new();
+    let (x_config, _) = configs();
+
+    let err = backend
+        .fetch_available_game(&x_config.multisig, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, TicTacToeError::NoAvailableGames));
+
+    let game_info = new_game(&backend, &x_config).await;
+    let multisig_addr = x_config.multisig.admin_address();
+    let err = backend
+        .find_mark(game_info.game_id, multisig_addr)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, TicTacToeError::MarkNotFound));
+}
+
+#[tokio::test]
+async fn test_x_wins() {
+    let backend = InMemoryBackend::new();
+    let (x_config, o_config) = play(
+        &backend,
+        &[(0, 0), (1, 1), (2, 2)],
+        &[(0, 1), (0, 2)],
+    )
+    .await;
+
+    let game = backend
+        .fetch_available_game(&x_config.multisig, None)
+        .await
+        .unwrap();
+    assert_eq!(game.finished, 1);
+    let x_addr = SuiAddress::from(&x_config.signer.public());
+    let o_addr = SuiAddress::from(&o_config.signer.public());
+    let trophies = backend.trophies(x_addr);
+    assert_eq!(trophies.len(), 1);
+    assert_eq!(trophies[0].winner, x_addr);
+    assert_eq!(trophies[0].loser, o_addr);
+    assert_eq!(trophies[0].played_as, 1);
+    assert_eq!(trophies[0].game_id, game.id.id.bytes);
+    assert!(backend.trophies(o_addr).is_empty());
+    // Mark is deleted at the end of the game
+    assert!(backend.find_mark(game.id.id.bytes, x_addr).await.is_err());
+    assert!(backend.find_mark(game.id.id.bytes, o_addr).await.is_err());
+}
+
+#[tokio::test]
+async fn test_o_wins_after_rejected_move() {
+    let backend = InMemoryBackend::new();
+    // O first tries the cell X already took, gets the mark back and plays again
+    let (x_config, o_config) = play(
+        &backend,
+        &[(0, 0), (0, 1), (2, 2)],
+        &[(0, 0), (2, 0), (1, 1), (0, 2)],
+    )
+    .await;
+
+    let game = backend
+        .fetch_available_game(&x_config.multisig, None)
+        .await
+        .unwrap();
+    assert_eq!(game.finished, 2);
+    let o_addr = SuiAddress::from(&o_config.signer.public());
+    assert_eq!(backend.trophies(o_addr).len(), 1);
+    assert_eq!(backend.trophies(o_addr)[0].played_as, 2);
+}
+
+/// Makes a move on the current board, as the play loop does once it observed it
+async fn make_move(
+    backend: &InMemoryBackend,
+    config: &Config,
+    game_id: ObjectID,
+    row: u8,
+    col: u8,
+) -> Result<MoveOutcome, TicTacToeError> {
+    let board = Board::from(&backend.fetch_game(game_id).await.unwrap());
+    make_move_on(backend, config, game_id, &board, row, col).await
+}
+
+async fn make_move_on(
+    backend: &InMemoryBackend,
+    config: &Config,
+    game_id: ObjectID,
+    board: &Board,
+    row: u8,
+    col: u8,
+) -> Result<MoveOutcome, TicTacToeError> {
+    let (row, col) = (
+        RowCol::try_from(row).unwrap(),
+        RowCol::try_from(col).unwrap(),
+    );
+    backend
+        .make_move(&config.signer, &config.multisig, game_id, board, row, col)
+        .await
+}
+
+#[tokio::test]
+async fn test_make_move() {
+    let backend = InMemoryBackend::new();
+    let (x_config, o_config) = configs();
+    let game_id = new_game(&backend, &x_config).await.game_id;
+
+    let first_turn = Board::from(&backend.fetch_game(game_id).await.unwrap());
+    assert_eq!(
+        make_move(&backend, &x_config, game_id, 0, 0).await.unwrap(),
+        MoveOutcome::Placed
+    );
+    // Not the turn of X anymore, but the same move already landed
+    assert_eq!(
+        make_move_on(&backend, &x_config, game_id, &first_turn, 0, 0)
+            .await
+            .unwrap(),
+        MoveOutcome::Placed
+    );
+    assert!(
+        make_move_on(&backend, &x_config, game_id, &first_turn, 1, 1)
+            .await
+            .is_err()
+    );
+    assert!(matches!(
+        make_move(&backend, &x_config, game_id, 1, 1).await,
+        Err(TicTacToeError::NotYourTurn {
+            player: Player::X,
+            ..
+        })
+    ));
+
+    // The contract passes the mark back to O
+    assert_eq!(
+        make_move(&backend, &o_config, game_id, 0, 0).await.unwrap(),
+        MoveOutcome::CellOccupied
+    );
+    let o_addr = SuiAddress::from(&o_config.signer.public());
+    assert!(backend.find_mark(game_id, o_addr).await.is_ok());
+    assert_eq!(
+        make_move(&backend, &o_config, game_id, 0, 1).await.unwrap(),
+        MoveOutcome::Placed
+    );
+
+    let third_turn = Board::from(&backend.fetch_game(game_id).await.unwrap());
+    assert_eq!(
+        make_move(&backend, &x_config, game_id, 1, 1).await.unwrap(),
+        MoveOutcome::Placed
+    );
+    // The mark of X has been in that cell since the first turn, it is not a move of the third one
+    assert!(
+        make_move_on(&backend, &x_config, game_id, &third_turn, 0, 0)
+            .await
+            .is_err()
+    );
+    assert_eq!(
+        make_move(&backend, &o_config, game_id, 0, 2).await.unwrap(),
+        MoveOutcome::Placed
+    );
+    assert_eq!(
+        make_move(&backend, &x_config, game_id, 2, 2).await.unwrap(),
+        MoveOutcome::Won(Player::X)
+    );
+    assert!(make_move(&backend, &o_config, game_id, 1, 0).await.is_err());
+}
+
+#[tokio::test]
+async fn test_make_move_resumes_after_sent_mark() {
+    let backend = InMemoryBackend::new();
+    let (x_config, _) = configs();
+    let game_info = new_game(&backend, &x_config).await;
+    backend
+        .send_mark_to_game(
+            &x_config.signer,
+            game_info.mark_id,
+            RowCol::First,
+            RowCol::Second,
+        )
+        .await
+        .unwrap();
+
+    // Only the sent move can be resumed, the other ones are told about it
+    let err = backend
+        .make_move(
+            &x_config.signer,
+            &x_config.multisig,
+            game_info.game_id,
+            &Board::new(),
+            RowCol::Second,
+            RowCol::Second,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        TicTacToeError::PendingMove { mark_id, placement: 3, .. } if mark_id == game_info.mark_id
+    ));
+    let outcome = backend
+        .make_move(
+            &x_config.signer,
+            &x_config.multisig,
+            game_info.game_id,
+            &Board::new(),
+            RowCol::First,
+            RowCol::Second,
+        )
+        .await
+        .unwrap();
+    assert_eq!(outcome, MoveOutcome::Placed);
+    let game = backend.fetch_game(game_info.game_id).await.unwrap();
+    // Column major
+    assert_eq!(game.gameboard[3], 1);
+}
+
+#[tokio::test]
+async fn test_play_resumes_from_store() {
+    let backend = InMemoryBackend::new();
+    let (x_config, o_config) = configs();
+    let path = store_path("resume");
+    let game_info = new_game(&backend, &x_config).await;
+
+    // X makes a move, and the CLI is stopped while waiting for O
+    let store = Mutex::new(GameStore::load(&path, Network::Testnet).unwrap());
+    let mut x_input = ScriptedInput(VecDeque::from([(0, 0)]));
+    let first_session = play_game(
+        &backend,
+        &x_config,
+        game_info.clone(),
+        &mut x_input,
+        poll_config(),
+        Some(&store),
+    );
+    assert!(timeout(Duration::from_millis(200), first_session)
+        .await
+        .is_err());
+    make_move(&backend, &o_config, game_info.game_id, 0, 1)
+        .await
+        .unwrap();
+
+    // The restarted CLI takes the game from the store and plays on
+    let store = Mutex::new(GameStore::load(&path, Network::Testnet).unwrap());
+    let game = backend.fetch_game(game_info.game_id).await.unwrap();
+    let resumed = resume_game_info(&backend, &x_config, &game, Some(&store))
+        .await
+        .unwrap();
+    assert_eq!(resumed.mark_id, game_info.mark_id);
+    let mut x_input = ScriptedInput(VecDeque::from([(1, 1), (2, 2)]));
+    let mut o_input = ScriptedInput(VecDeque::from([(0, 2)]));
+    let (x_res, o_res) = tokio::join!(
+        play_game(
+            &backend,
+            &x_config,
+            resumed,
+            &mut x_input,
+            poll_config(),
+            Some(&store)
+        ),
+        play_game(
+            &backend,
+            &o_config,
+            game_info.clone(),
+            &mut o_input,
+            poll_config(),
+            None
+        ),
+    );
+    x_res.unwrap();
+    o_res.unwrap();
+
+    // The moves of both sessions are recorded
+    let store = store.into_inner().unwrap();
+    let record = store
+        .game(x_config.multisig.admin_address(), game_info.game_id)
+        .unwrap();
+    let moves: Vec<(u8, u8, u8)> = record
+        .moves
+        .iter()
+        .map(|made| (made.turn, made.row, made.col))
+        .collect();
+    assert_eq!(moves, vec![(0, 0, 0), (2, 1, 1), (4, 2, 2)]);
+    assert!(record.last_seen_version.is_some());
+}
+
+#[tokio::test]
+async fn test_import_game() {
+    let backend = InMemoryBackend::new();
+    let (x_config, o_config) = configs();
+    let notation = GameNotation::from_str(&format!(
+        "game {}\nmultisig {}\nx {}\no {}\nmoves 0,0 1,1 1,1? 0,1",
+        ObjectID::random(),
+        x_config.multisig.admin_address(),
+        x_config.multisig.x_address(),
+        x_config.multisig.o_address(),
+    ))
+    .unwrap();
+    let game_info = backend
+        .import_game(notation.game.clone(), notation.multisig)
+        .unwrap()
+        .unwrap();
+    assert_eq!(game_info.game_id, notation.game_id());
+    assert_eq!(
+        backend.fetch_game(game_info.game_id).await.unwrap(),
+        notation.game
+    );
+    assert!(backend
+        .import_game(notation.game.clone(), notation.multisig)
+        .is_err());
+
+    // The imported game is played on from where it was
+    let o_addr = SuiAddress::from(&o_config.signer.public());
+    assert_eq!(backend.mark_owner(game_info.mark_id).await.unwrap(), o_addr);
+    assert_eq!(
+        make_move(&backend, &o_config, game_info.game_id, 1, 1)
+            .await
+            .unwrap(),
+        MoveOutcome::CellOccupied
+    );
+    assert_eq!(
+        make_move(&backend, &o_config, game_info.game_id, 2, 2)
+            .await
+            .unwrap(),
+        MoveOutcome::Placed
+    );
+    assert_eq!(
+        make_move(&backend, &x_config, game_info.game_id, 0, 2)
+            .await
+            .unwrap(),
+        MoveOutcome::Won(Player::X)
+    );
+
+    // A finished game has no mark to play it with
+    let game = backend.fetch_game(game_info.game_id).await.unwrap();
+    let other_backend = InMemoryBackend::new();
+    assert!(other_backend
+        .import_game(game.clone(), notation.multisig)
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        other_backend.fetch_game(game_info.game_id).await.unwrap(),
+        game
+    );
+}
+
+#[tokio::test]
+async fn test_draw() {
+    let backend = InMemoryBackend::new();
+    let (x_config, o_config) = play(
+        &backend,
+        &[(0, 0), (2, 0), (1, 1), (2, 1), (1, 2)],
+        &[(1, 0), (0, 1), (0, 2), (2, 2)],
+    )
+    .await;
+
+    let game = backend
+        .fetch_available_game(&x_config.multisig, None)
+        .await
+        .unwrap();
+    assert_eq!(game.finished, 3);
+    assert!(backend
+        .trophies(SuiAddress::from(&x_config.signer.public()))
+        .is_empty());
+    assert!(backend
+        .trophies(SuiAddress::from(&o_config.signer.public()))
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_delete_game() {
+    let backend = InMemoryBackend::new();
+    let (x_config, o_config) = configs();
+    let game_id = new_game(&backend, &x_config).await.game_id;
+
+    // Unfinished games cannot be deleted
+    let err = backend
+        .delete_game(&x_config.signer, &x_config.multisig, game_id)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        TicTacToeError::MoveAbort {
+            code: AbortCode::ETriedToCheat,
+            ..
+        }
+    ));
+    assert!(backend.fetch_game(game_id).await.is_ok());
+
+    for (config, row, col) in [
+        (&x_config, 0, 0),
+        (&o_config, 0, 1),
+        (&x_config, 1, 1),
+        (&o_config, 0, 2),
+        (&x_config, 2, 2),
+    ] {
+        make_move(&backend, config, game_id, row, col)
+            .await
+            .unwrap();
+    }
+
+    // Finished games can be deleted by any of the participants, and only by them
+    let (outsider, _) = configs();
+    assert!(backend
+        .delete_game(&outsider.signer, &x_config.multisig, game_id)
+        .await
+        .is_err());
+    backend
+        .delete_game(&o_config.signer, &x_config.multisig, game_id)
+        .await
+        .unwrap();
+    let err = backend.fetch_game(game_id).await.unwrap_err();
+    assert!(matches!(err, TicTacToeError::GameNotFound(_)));
+    // The trophy is kept
+    let x_addr = SuiAddress::from(&x_config.signer.public());
+    assert_eq!(backend.trophies(x_addr).len(), 1);
+}
+
+#[tokio::test]
+async fn test_trophy_queries() {
+    let backend = InMemoryBackend::new();
+    let (x_config, o_config) = configs();
+    for _ in 0..3 {
+        let game_id = backend
+            .create_game(&x_config.signer, &x_config.multisig)
+            .await
+            .unwrap()
+            .game_id;
+        for (config, row, col) in [
+            (&x_config, 0, 0),
+            (&o_config, 0, 1),
+            (&x_config, 1, 1),
+            (&o_config, 0, 2),
+            (&x_config, 2, 2),
+        ] {
+            make_move(&backend, config, game_id, row, col)
+                .await
+                .unwrap();
+        }
+    }
+    let x_addr = SuiAddress::from(&x_config.signer.public());
+
+    let first = backend.fetch_trophies(x_addr, None, Some(2)).await.unwrap();
+    assert_eq!(first.data.len(), 2);
+    assert!(first.has_next_page);
+    let second = backend
+        .fetch_trophies(x_addr, first.next_cursor, Some(2))
+        .await
+        .unwrap();
+    assert_eq!(second.data.len(), 1);
+    assert!(!second.has_next_page);
+    let all = backend.fetch_trophies(x_addr, None, None).await.unwrap();
+    assert_eq!(all.data.len(), 3);
+    assert_eq!([first.data, second.data].concat(), all.data);
+
+    let trophy = &all.data[0];
+    assert_eq!(backend.fetch_trophy(trophy.id).await.unwrap(), *trophy);
+    let err = backend.fetch_trophy(ObjectID::random()).await.unwrap_err();
+    assert!(matches!(err, TicTacToeError::TrophyNotFound(_)));
+}
+
+#[tokio::test]
+async fn test_mixed_key_schemes() {
+    for x_scheme in SCHEMES {
+        for o_scheme in SCHEMES {
+            let backend = InMemoryBackend::new();
+            let (x_config, _) = play_with_configs(
+                &backend,
+                configs_with_schemes(x_scheme, o_scheme),
+                &[(0, 0), (1, 1), (2, 2)],
+                &[(0, 1), (0, 2)],
+            )
+            .await;
+
+            let x_addr = SuiAddress::from(&x_config.signer.public());
+            let trophies = backend.trophies(x_addr);
+            assert_eq!(trophies.len(), 1, "{:?} against {:?}", x_scheme, o_scheme);
+            assert_eq!(trophies[0].played_as, 1);
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Resolves the opponent from a public key, the address book or the keystore.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{EncodeDecodeBase64, SignatureScheme, SuiKeyPair};
+
+use rust_cli_client::keys::{resolve_opponent, AddressBook};
+
+mod common;
+use common::new_key_pair;
+
+/// Directory of its own for the files of a test, which is empty
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join(format!("multisig-tic-tac-toe-keys-{}", std::process::id()))
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Keystore holding the key, under the alias
+fn keystore_with(dir: &Path, key_pair: &SuiKeyPair, alias: &str) -> PathBuf {
+    let path = dir.join("sui.keystore");
+    fs::write(
+        &path,
+        serde_json::to_string(&[key_pair.encode_base64()]).unwrap(),
+    )
+    .unwrap();
+    let aliases = serde_json::json!([{
+        "alias": alias,
+        "public_key_base64": key_pair.public().encode_base64(),
+    }]);
+    fs::write(path.with_extension("aliases"), aliases.to_string()).unwrap();
+    path
+}
+
+#[test]
+fn test_address_book() {
+    let dir = test_dir("address_book");
+    let path = dir.join("address_book.toml");
+    assert!(AddressBook::load(&path).unwrap().get("alice").is_none());
+
+    let alice = new_key_pair(SignatureScheme::ED25519).public();
+    fs::write(&path, format!("alice = \"{}\"", alice.encode_base64())).unwrap();
+    let address_book = AddressBook::load(&path).unwrap();
+    assert_eq!(address_book.get("alice"), Some(&alice));
+    assert!(address_book.get("bob").is_none());
+
+    fs::write(&path, "alice = \"not a key\"").unwrap();
+    let err = AddressBook::load(&path).unwrap_err();
+    assert!(err.to_string().contains("alice"));
+}
+
+#[test]
+fn test_resolve_opponent() {
+    let dir = test_dir("resolve_opponent");
+    let alice = new_key_pair(SignatureScheme::ED25519).public();
+    fs::write(
+        dir.join("address_book.toml"),
+        format!("alice = \"{}\"", alice.encode_base64()),
+    )
+    .unwrap();
+    let address_book = AddressBook::load(&dir.join("address_book.toml")).unwrap();
+    let bob = new_key_pair(SignatureScheme::ED25519);
+    let keystore = keystore_with(&dir, &bob, "bob");
+    let keystore = Some(keystore.as_path());
+
+    // Raw public key
+    let carol = new_key_pair(SignatureScheme::ED25519).public();
+    assert_eq!(
+        resolve_opponent(&carol.encode_base64(), &address_book, keystore).unwrap(),
+        carol
+    );
+    // Name in the address book
+    assert_eq!(
+        resolve_opponent("alice", &address_book, keystore).unwrap(),
+        alice
+    );
+    // Alias and address in the keystore
+    assert_eq!(
+        resolve_opponent("bob", &address_book, keystore).unwrap(),
+        bob.public()
+    );
+    let bob_addr = SuiAddress::from(&bob.public()).to_string();
+    assert_eq!(
+        resolve_opponent(&bob_addr, &address_book, keystore).unwrap(),
+        bob.public()
+    );
+    // Nowhere
+    let err = resolve_opponent("dave", &address_book, keystore).unwrap_err();
+    assert!(err.to_string().contains("dave"));
+    let dave_addr = SuiAddress::random_for_testing_only().to_string();
+    assert!(resolve_opponent(&dave_addr, &address_book, keystore).is_err());
+}
@@ -0,0 +1,115 @@
+//! Decodes the aborts of the contract out of the errors of failed transactions.
+
+use sui_types::base_types::ObjectID;
+
+use rust_cli_client::error::{AbortCode, TicTacToeError};
+use rust_cli_client::move_abort::MoveAbort;
+
+const PACKAGE: &str = "7b4a34f6a011794f0ecbe5e5beb96102d3eef6122eb929b9f50a8d757bfbdd67";
+
+fn package_id() -> ObjectID {
+    ObjectID::from_hex_literal(&format!("0x{}", PACKAGE)).unwrap()
+}
+
+fn abort_error(module: &str, function: &str, code: u64) -> String {
+    abort_error_in(PACKAGE, module, function, code)
+}
+
+fn abort_error_in(package: &str, module: &str, function: &str, code: u64) -> String {
+    format!(
+        "MoveAbort(MoveLocation {{ module: ModuleId {{ address: {}, name: Identifier(\"{}\") }}, \
+         function: 1, instruction: 9, function_name: Some(\"{}\") }}, {}) in command 0",
+        package, module, function, code
+    )
+}
+
+#[test]
+fn test_parse() {
+    let abort = MoveAbort::parse(&abort_error("multisig_tic_tac_toe", "send_mark_to_game", 1));
+    assert_eq!(
+        abort,
+        Some(MoveAbort {
+            package: Some(package_id()),
+            module: Some("multisig_tic_tac_toe".to_string()),
+            function: Some("send_mark_to_game".to_string()),
+            code: 1,
+        })
+    );
+    assert_eq!(
+        abort.unwrap().contract_code(package_id()),
+        Some(AbortCode::ETriedToCheat)
+    );
+}
+
+#[test]
+fn test_contract_codes() {
+    for (function, code, expected) in [
+        ("get_index", 0, AbortCode::EInvalidSize),
+        ("delete_game", 1, AbortCode::ETriedToCheat),
+        ("place_mark", 2, AbortCode::EMarkIsFromDifferentGame),
+    ] {
+        let error = abort_error("multisig_tic_tac_toe", function, code);
+        match TicTacToeError::from_execution_failure(&error, package_id()) {
+            TicTacToeError::MoveAbort {
+                code,
+                function: Some(aborted_in),
+            } => {
+                assert_eq!(code, expected);
+                assert_eq!(aborted_in, function);
+            }
+            e => panic!("Unexpected error: {}", e),
+        }
+    }
+}
+
+#[test]
+fn test_explanations() {
+    let error = abort_error("multisig_tic_tac_toe", "delete_game", 1);
+    assert_eq!(
+        TicTacToeError::from_execution_failure(&error, package_id()).to_string(),
+        "The game is not finished yet (ETriedToCheat (1)). Finish the game before deleting it"
+    );
+}
+
+#[test]
+fn test_other_failures() {
+    // Aborts of other modules are not the contract's
+    let error = abort_error("coin", "split", 0);
+    assert_eq!(
+        MoveAbort::parse(&error)
+            .unwrap()
+            .contract_code(package_id()),
+        None
+    );
+    assert!(matches!(
+        TicTacToeError::from_execution_failure(&error, package_id()),
+        TicTacToeError::ExecutionFailed(_)
+    ));
+
+    // Nor are the aborts of a module of the same name in another package
+    let other_package = "0x1111111111111111111111111111111111111111111111111111111111111111";
+    let error = abort_error_in(other_package, "multisig_tic_tac_toe", "place_mark", 1);
+    assert_eq!(
+        MoveAbort::parse(&error)
+            .unwrap()
+            .contract_code(package_id()),
+        None
+    );
+    assert!(matches!(
+        TicTacToeError::from_execution_failure(&error, package_id()),
+        TicTacToeError::ExecutionFailed(_)
+    ));
+
+    // Unknown codes are not decoded
+    let error = abort_error("multisig_tic_tac_toe", "place_mark", 42);
+    assert!(matches!(
+        TicTacToeError::from_execution_failure(&error, package_id()),
+        TicTacToeError::ExecutionFailed(_)
+    ));
+
+    assert_eq!(MoveAbort::parse("InsufficientGas"), None);
+    assert!(matches!(
+        TicTacToeError::from_execution_failure("InsufficientGas", package_id()),
+        TicTacToeError::InsufficientGas(_)
+    ));
+}
@@ -0,0 +1,155 @@
+//! Signs sponsored multisig transactions with every combination of key schemes.
+
+use sui_types::base_types::{random_object_ref, SuiAddress};
+use sui_types::crypto::{SignatureScheme, SuiKeyPair};
+use sui_types::multisig::MultiSigPublicKey;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::signature::{AuthenticatorTrait, VerifyParams};
+use sui_types::transaction::TransactionData;
+
+use rust_cli_client::multi_sig_1_out_of_n::MultiSig1OutOfN;
+use rust_cli_client::startup::{Config, Player};
+
+mod common;
+use common::{new_key_pair, SCHEMES};
+
+fn sponsored_tx_data(multisig: &MultiSig1OutOfN, sponsor: &SuiKeyPair) -> TransactionData {
+    TransactionData::new_programmable_allow_sponsor(
+        multisig.admin_address(),
+        vec![random_object_ref()],
+        ProgrammableTransactionBuilder::new().finish(),
+        10_000_000,
+        1000,
+        SuiAddress::from(&sponsor.public()),
+    )
+}
+
+#[test]
+fn test_roles_of_mixed_schemes() {
+    for x_scheme in SCHEMES {
+        for o_scheme in SCHEMES {
+            let x_signer = new_key_pair(x_scheme);
+            let o_signer = new_key_pair(o_scheme);
+            let multisig =
+                MultiSig1OutOfN::new(vec![x_signer.public(), o_signer.public()]).unwrap();
+            assert_eq!(multisig.role_of(&x_signer.public()), Some(Player::X));
+            assert_eq!(multisig.role_of(&o_signer.public()), Some(Player::O));
+            assert_eq!(multisig.x_address(), SuiAddress::from(&x_signer.public()));
+            assert_eq!(multisig.o_address(), SuiAddress::from(&o_signer.public()));
+        }
+    }
+}
+
+#[test]
+fn test_sign_sponsored_transaction_mixed_schemes() {
+    for x_scheme in SCHEMES {
+        for o_scheme in SCHEMES {
+            let x_signer = new_key_pair(x_scheme);
+            let o_signer = new_key_pair(o_scheme);
+            let multisig =
+                MultiSig1OutOfN::new(vec![x_signer.public(), o_signer.public()]).unwrap();
+
+            for signer in [&x_signer, &o_signer] {
+                let tx_data = sponsored_tx_data(&multisig, signer);
+                let transaction = multisig
+                    .sign_sponsored_transaction(signer, tx_data)
+                    .unwrap();
+                let data = transaction.data();
+                let [sponsor_sig, multisig_sig] = data.tx_signatures() else {
+                    panic!("Expected the signatures of the sponsor and of the multisig");
+                };
+                sponsor_sig
+                    .verify_authenticator(
+                        data.intent_message(),
+                        SuiAddress::from(&signer.public()),
+                        None,
+                        &VerifyParams::default(),
+                    )
+                    .unwrap_or_else(|e| panic!("{:?}/{:?} sponsor: {}", x_scheme, o_scheme, e));
+                multisig_sig
+                    .verify_authenticator(
+                        data.intent_message(),
+                        multisig.admin_address(),
+                        None,
+                        &VerifyParams::default(),
+                    )
+                    .unwrap_or_else(|e| panic!("{:?}/{:?} multisig: {}", x_scheme, o_scheme, e));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_outsider_cannot_sign() {
+    for scheme in SCHEMES {
+        let multisig = MultiSig1OutOfN::new(vec![
+            new_key_pair(SignatureScheme::ED25519).public(),
+            new_key_pair(SignatureScheme::Secp256r1).public(),
+        ])
+        .unwrap();
+        let outsider = new_key_pair(scheme);
+        let tx_data = sponsored_tx_data(&multisig, &outsider);
+        assert!(multisig
+            .sign_sponsored_transaction(&outsider, tx_data)
+            .is_err());
+    }
+}
+
+#[test]
+fn test_rejects_wrong_key_count() {
+    let pub_key = new_key_pair(SignatureScheme::ED25519).public();
+    let multisig_pub_key = MultiSigPublicKey::new(vec![pub_key.clone()], vec![1], 1).unwrap();
+    let err = MultiSig1OutOfN::try_from(multisig_pub_key).unwrap_err();
+    assert!(err.to_string().starts_with("Incorrect number of pubkeys"));
+    assert!(MultiSig1OutOfN::new(vec![pub_key]).is_err());
+}
+
+#[test]
+fn test_rejects_wrong_weights() {
+    let pub_keys = vec![
+        new_key_pair(SignatureScheme::ED25519).public(),
+        new_key_pair(SignatureScheme::Secp256k1).public(),
+    ];
+    let multisig_pub_key = MultiSigPublicKey::new(pub_keys, vec![1, 2], 1).unwrap();
+    let err = MultiSig1OutOfN::try_from(multisig_pub_key).unwrap_err();
+    assert!(err.to_string().starts_with("Weight of"));
+}
+
+#[test]
+fn test_rejects_wrong_threshold() {
+    let pub_keys = vec![
+        new_key_pair(SignatureScheme::ED25519).public(),
+        new_key_pair(SignatureScheme::Secp256r1).public(),
+    ];
+    let multisig_pub_key = MultiSigPublicKey::new(pub_keys, vec![1, 1], 2).unwrap();
+    let err = MultiSig1OutOfN::try_from(multisig_pub_key).unwrap_err();
+    assert!(err.to_string().starts_with("Multisig threshold should be 1"));
+}
+
+#[test]
+fn test_address_is_deterministic() {
+    let x_pub_key = new_key_pair(SignatureScheme::ED25519).public();
+    let o_pub_key = new_key_pair(SignatureScheme::Secp256k1).public();
+    let multisig = MultiSig1OutOfN::new(vec![x_pub_key.clone(), o_pub_key.clone()]).unwrap();
+    let same = MultiSig1OutOfN::new(vec![x_pub_key.clone(), o_pub_key.clone()]).unwrap();
+    let swapped = MultiSig1OutOfN::new(vec![o_pub_key, x_pub_key]).unwrap();
+    assert_eq!(multisig.admin_address(), same.admin_address());
+    assert_ne!(multisig.admin_address(), swapped.admin_address());
+}
+
+#[test]
+fn test_non_participant_has_no_role() {
+    let multisig = MultiSig1OutOfN::new(vec![
+        new_key_pair(SignatureScheme::ED25519).public(),
+        new_key_pair(SignatureScheme::Secp256k1).public(),
+    ])
+    .unwrap();
+    let outsider = new_key_pair(SignatureScheme::ED25519);
+    assert_eq!(multisig.role_of(&outsider.public()), None);
+
+    let config = Config {
+        signer: outsider,
+        multisig,
+    };
+    assert!(config.current_player().is_err());
+}
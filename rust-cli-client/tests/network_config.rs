@@ -0,0 +1,166 @@
+//! Resolves the network settings from each layer: CLI flags over environment variables over the
+//! config file over the built-in defaults.
+
+use std::collections::HashMap;
+
+use sui_types::base_types::ObjectID;
+
+use rust_cli_client::network_config::{
+    network_package_id_env, network_rpc_url_env, network_ws_url_env, ConfigFile, Network,
+    NetworkConfig, NetworkOpts, NetworkProfile, NETWORK_ENV, PACKAGE_ID_ENV, RPC_URL_ENV,
+};
+
+fn resolve(
+    file: &ConfigFile,
+    env_vars: &[(&str, &str)],
+    opts: &NetworkOpts,
+) -> anyhow::Result<NetworkConfig> {
+    let env_vars: HashMap<String, String> = env_vars
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    NetworkConfig::resolve(file, |key| env_vars.get(key).cloned(), opts)
+}
+
+fn package_id(byte: u8) -> ObjectID {
+    ObjectID::new([byte; 32])
+}
+
+/// A config file selecting localnet, with its package ID
+fn localnet_file() -> ConfigFile {
+    ConfigFile {
+        network: Some(Network::Localnet),
+        localnet: Some(NetworkProfile {
+            rpc_url: None,
+            ws_url: None,
+            package_id: Some(package_id(1)),
+        }),
+        ..ConfigFile::default()
+    }
+}
+
+#[test]
+fn test_defaults() {
+    let config = resolve(&ConfigFile::default(), &[], &NetworkOpts::default()).unwrap();
+    assert_eq!(config.network, Network::Testnet);
+    assert_eq!(config.rpc_url, "https://rpc.testnet.sui.io:443");
+    assert_eq!(config.ws_url, "wss://rpc.testnet.sui.io:443");
+
+    // Only testnet has a built-in package ID
+    let opts = NetworkOpts {
+        network: Some(Network::Localnet),
+        ..NetworkOpts::default()
+    };
+    assert!(resolve(&ConfigFile::default(), &[], &opts).is_err());
+}
+
+#[test]
+fn test_file_over_defaults() {
+    let config = resolve(&localnet_file(), &[], &NetworkOpts::default()).unwrap();
+    assert_eq!(config.network, Network::Localnet);
+    assert_eq!(config.rpc_url, "http://127.0.0.1:9000");
+    assert_eq!(config.ws_url, "ws://127.0.0.1:9000");
+    assert_eq!(config.package_id, package_id(1));
+}
+
+#[test]
+fn test_env_over_file() {
+    let rpc_url_env = network_rpc_url_env(Network::Localnet);
+    let package_id_env = network_package_id_env(Network::Localnet);
+    let package_id_2 = package_id(2).to_string();
+    let env_vars = [
+        (rpc_url_env.as_str(), "http://127.0.0.1:9100"),
+        (package_id_env.as_str(), package_id_2.as_str()),
+    ];
+    let config = resolve(&localnet_file(), &env_vars, &NetworkOpts::default()).unwrap();
+    assert_eq!(config.rpc_url, "http://127.0.0.1:9100");
+    assert_eq!(config.ws_url, "ws://127.0.0.1:9100");
+    assert_eq!(config.package_id, package_id(2));
+
+    // The package ID of localnet is not used on devnet, which has none
+    let err = resolve(
+        &localnet_file(),
+        &[(NETWORK_ENV, "devnet"), (package_id_env.as_str(), "0x1")],
+        &NetworkOpts::default(),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("devnet"));
+
+    assert!(resolve(
+        &localnet_file(),
+        &[(NETWORK_ENV, "nonet")],
+        &NetworkOpts::default()
+    )
+    .is_err());
+}
+
+#[test]
+fn test_flags_over_env() {
+    let package_id_env = network_package_id_env(Network::Localnet);
+    let ws_url_env = network_ws_url_env(Network::Localnet);
+    let package_id_2 = package_id(2).to_string();
+    let env_vars = [
+        (NETWORK_ENV, "devnet"),
+        (package_id_env.as_str(), package_id_2.as_str()),
+        (ws_url_env.as_str(), "ws://127.0.0.1:9101"),
+    ];
+    let opts = NetworkOpts {
+        network: Some(Network::Localnet),
+        rpc_url: Some("http://127.0.0.1:9200".to_string()),
+        package_id: Some(package_id(3)),
+        ..NetworkOpts::default()
+    };
+    let config = resolve(&localnet_file(), &env_vars, &opts).unwrap();
+    assert_eq!(config.network, Network::Localnet);
+    assert_eq!(config.rpc_url, "http://127.0.0.1:9200");
+    // Only given for the websocket, which is not derived from the rpc url then
+    assert_eq!(config.ws_url, "ws://127.0.0.1:9101");
+    assert_eq!(config.package_id, package_id(3));
+}
+
+#[test]
+fn test_env_is_per_network() {
+    // As written by `setup/publish.sh` for localnet
+    let published_package_id = package_id(4).to_string();
+    let env_vars = [
+        (RPC_URL_ENV, "http://localhost:9000"),
+        (PACKAGE_ID_ENV, published_package_id.as_str()),
+    ];
+
+    // It selects localnet if no other network is given
+    let config = resolve(&ConfigFile::default(), &env_vars, &NetworkOpts::default()).unwrap();
+    assert_eq!(config.network, Network::Localnet);
+    assert_eq!(config.rpc_url, "http://localhost:9000");
+    assert_eq!(config.package_id, package_id(4));
+
+    // But it is left out on other networks
+    let opts = NetworkOpts {
+        network: Some(Network::Testnet),
+        ..NetworkOpts::default()
+    };
+    let testnet = resolve(&ConfigFile::default(), &[], &opts).unwrap();
+    assert_eq!(
+        resolve(&ConfigFile::default(), &env_vars, &opts).unwrap(),
+        testnet
+    );
+}
+
+#[test]
+fn test_network_of_rpc_url() {
+    assert_eq!(
+        Network::of_rpc_url("https://rpc.devnet.sui.io:443"),
+        Network::Devnet
+    );
+    assert_eq!(
+        Network::of_rpc_url("https://rpc.testnet.sui.io:443/"),
+        Network::Testnet
+    );
+    assert_eq!(
+        Network::of_rpc_url("http://127.0.0.1:9000"),
+        Network::Localnet
+    );
+    assert_eq!(
+        Network::of_rpc_url("https://fullnode.example.com"),
+        Network::Custom
+    );
+}
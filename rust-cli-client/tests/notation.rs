@@ -0,0 +1,95 @@
+//! Writes and reads games in their text and JSON notations.
+
+use std::str::FromStr;
+
+use sui_types::base_types::{ObjectID, SuiAddress};
+
+use rust_cli_client::game_engine::{MARK_EMPTY, MARK_O, MARK_X};
+use rust_cli_client::notation::{GameNotation, NotationMove};
+
+fn notation(moves: &str) -> anyhow::Result<GameNotation> {
+    let moves = moves
+        .split_whitespace()
+        .map(NotationMove::from_str)
+        .collect::<anyhow::Result<_>>()?;
+    GameNotation::new(
+        ObjectID::random(),
+        SuiAddress::random_for_testing_only(),
+        SuiAddress::random_for_testing_only(),
+        SuiAddress::random_for_testing_only(),
+        moves,
+    )
+}
+
+#[test]
+fn test_moves() {
+    let notation = notation("0,0 1,1 1,1? 0,1 2,2 0,2").unwrap();
+    assert_eq!(
+        notation.moves[2],
+        NotationMove {
+            row: 1,
+            col: 1,
+            rejected: true
+        }
+    );
+    // X won on the first row, after trying the cell in the middle taken by O
+    assert_eq!(
+        notation.game.gameboard,
+        vec![
+            MARK_X, MARK_EMPTY, MARK_EMPTY, MARK_X, MARK_O, MARK_EMPTY, MARK_X, MARK_EMPTY, MARK_O
+        ]
+    );
+    assert_eq!(notation.game.finished, 1);
+    assert_eq!(notation.game.cur_turn, 4);
+
+    // Cells as `RowCol` parses them
+    assert_eq!(
+        NotationMove::from_str("first,Third").unwrap(),
+        NotationMove {
+            row: 0,
+            col: 2,
+            rejected: false
+        }
+    );
+}
+
+#[test]
+fn test_text_round_trip() {
+    let notation = notation("1,1 0,0 2,2").unwrap();
+    let text = notation.to_string();
+    assert!(text.ends_with("moves 1,1 0,0 2,2\n"));
+    assert_eq!(GameNotation::from_str(&text).unwrap(), notation);
+
+    // Comments and blank lines are skipped
+    let commented = format!("# Reported in a bug\n\n{}", text);
+    assert_eq!(GameNotation::decode(&commented).unwrap(), notation);
+}
+
+#[test]
+fn test_json_round_trip() {
+    let notation = notation("1,1 0,0 0,0? 2,2").unwrap();
+    let json = notation.to_json().unwrap();
+    assert_eq!(GameNotation::decode(&json).unwrap(), notation);
+
+    // The game has to be the one the moves lead to
+    let mut tampered = notation.clone();
+    tampered.game.gameboard[8] = MARK_EMPTY;
+    assert!(GameNotation::from_json(&tampered.to_json().unwrap()).is_err());
+}
+
+#[test]
+fn test_invalid_notation() {
+    // Out of the gameboard
+    assert!(notation("3,0").is_err());
+    assert!(notation("0").is_err());
+    // Taken cell not marked as rejected, and the other way around
+    assert!(notation("0,0 0,0").is_err());
+    assert!(notation("0,0 1,1?").is_err());
+    // Moves after the end of the game
+    assert!(notation("0,0 0,1 1,0 1,1 2,0 2,2").is_err());
+
+    let text = notation("0,0").unwrap().to_string();
+    let missing_game = text.lines().skip(1).collect::<Vec<_>>().join("\n");
+    assert!(GameNotation::from_str(&missing_game).is_err());
+    assert!(GameNotation::from_str(&format!("{}board 0\n", text)).is_err());
+}
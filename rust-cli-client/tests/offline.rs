@@ -0,0 +1,119 @@
+//! Signs sponsored multisig transactions in separate steps, as they are passed around in files.
+
+use sui_types::base_types::{random_object_ref, SuiAddress};
+use sui_types::crypto::{EncodeDecodeBase64, SignatureScheme, SuiKeyPair};
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::signature::{AuthenticatorTrait, VerifyParams};
+use sui_types::transaction::TransactionData;
+
+use rust_cli_client::multi_sig_1_out_of_n::MultiSig1OutOfN;
+use rust_cli_client::offline::{decode_signature, decode_tx_data, encode_tx_data, sign_tx_data};
+
+mod common;
+use common::{new_key_pair, SCHEMES};
+
+fn sponsored_tx_data(multisig: &MultiSig1OutOfN, sponsor: SuiAddress) -> TransactionData {
+    TransactionData::new_programmable_allow_sponsor(
+        multisig.admin_address(),
+        vec![random_object_ref()],
+        ProgrammableTransactionBuilder::new().finish(),
+        10_000_000,
+        1000,
+        sponsor,
+    )
+}
+
+/// Player X and player O, with keys of the given schemes
+fn players(
+    x_scheme: SignatureScheme,
+    o_scheme: SignatureScheme,
+) -> (SuiKeyPair, SuiKeyPair, MultiSig1OutOfN) {
+    let x_signer = new_key_pair(x_scheme);
+    let o_signer = new_key_pair(o_scheme);
+    let multisig = MultiSig1OutOfN::new(vec![x_signer.public(), o_signer.public()]).unwrap();
+    (x_signer, o_signer, multisig)
+}
+
+#[test]
+fn test_encoding_round_trip() {
+    for scheme in SCHEMES {
+        let (x_signer, _, multisig) = players(scheme, SignatureScheme::ED25519);
+        let tx_data = sponsored_tx_data(&multisig, SuiAddress::from(&x_signer.public()));
+        let encoded = encode_tx_data(&tx_data).unwrap();
+        // Files may end with a new line
+        assert_eq!(decode_tx_data(&format!("{}\n", encoded)).unwrap(), tx_data);
+        assert!(decode_tx_data("not base64").is_err());
+
+        let signature = sign_tx_data(&x_signer, &tx_data);
+        assert_eq!(
+            decode_signature(&signature.encode_base64()).unwrap(),
+            signature
+        );
+    }
+}
+
+#[test]
+fn test_combine_signatures() {
+    for x_scheme in SCHEMES {
+        for o_scheme in SCHEMES {
+            let (x_signer, o_signer, multisig) = players(x_scheme, o_scheme);
+            // X pays and O signs for the multisig, on another machine
+            let sponsor = SuiAddress::from(&x_signer.public());
+            let tx_data =
+                decode_tx_data(&encode_tx_data(&sponsored_tx_data(&multisig, sponsor)).unwrap())
+                    .unwrap();
+            let signatures = vec![
+                sign_tx_data(&o_signer, &tx_data),
+                sign_tx_data(&x_signer, &tx_data),
+            ];
+
+            let transaction = multisig.combine_signatures(tx_data, signatures).unwrap();
+            let data = transaction.data();
+            let [sponsor_sig, multisig_sig] = data.tx_signatures() else {
+                panic!("Expected the signatures of the sponsor and of the multisig");
+            };
+            sponsor_sig
+                .verify_authenticator(
+                    data.intent_message(),
+                    sponsor,
+                    None,
+                    &VerifyParams::default(),
+                )
+                .unwrap();
+            multisig_sig
+                .verify_authenticator(
+                    data.intent_message(),
+                    multisig.admin_address(),
+                    None,
+                    &VerifyParams::default(),
+                )
+                .unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_missing_signatures() {
+    let (x_signer, o_signer, multisig) =
+        players(SignatureScheme::ED25519, SignatureScheme::Secp256k1);
+    let tx_data = sponsored_tx_data(&multisig, SuiAddress::from(&x_signer.public()));
+
+    // The sponsor is needed
+    let signatures = vec![sign_tx_data(&o_signer, &tx_data)];
+    assert!(multisig
+        .combine_signatures(tx_data.clone(), signatures)
+        .is_err());
+
+    // Outsiders cannot sign for the multisig
+    let outsider = new_key_pair(SignatureScheme::Secp256r1);
+    let outsider_tx_data = sponsored_tx_data(&multisig, SuiAddress::from(&outsider.public()));
+    let signatures = vec![sign_tx_data(&outsider, &outsider_tx_data)];
+    assert!(multisig
+        .combine_signatures(outsider_tx_data, signatures)
+        .is_err());
+
+    // Nor can signatures of another transaction be used
+    let other_tx_data = sponsored_tx_data(&multisig, SuiAddress::from(&x_signer.public()));
+    let signatures = vec![sign_tx_data(&x_signer, &other_tx_data)];
+    assert!(multisig.combine_signatures(tx_data, signatures).is_err());
+}
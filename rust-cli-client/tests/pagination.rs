@@ -0,0 +1,134 @@
+//! Follows the cursors of a fake paginated query, and pages its items again as the client does.
+
+use std::cell::RefCell;
+
+use futures::{future, pin_mut, StreamExt, TryStreamExt};
+use sui_sdk::rpc_types::Page;
+
+use rust_cli_client::pagination::{paginate, paginate_from, take_page};
+
+/// Pages of the numbers up to `len`, the cursor being the last number of the previous page
+fn fake_page(cursor: Option<u32>, page_size: u32, len: u32) -> Page<u32, u32> {
+    let start = cursor.map_or(0, |c| c + 1);
+    let end = (start + page_size).min(len);
+    Page {
+        data: (start..end).collect(),
+        next_cursor: end.checked_sub(1),
+        has_next_page: end < len,
+    }
+}
+
+#[tokio::test]
+async fn test_follows_cursors() {
+    let cursors = RefCell::new(vec![]);
+    let items: Vec<u32> = paginate(|cursor| {
+        cursors.borrow_mut().push(cursor);
+        async move { Ok::<_, String>(fake_page(cursor, 3, 10)) }
+    })
+    .try_collect()
+    .await
+    .unwrap();
+
+    assert_eq!(items, (0..10).collect::<Vec<_>>());
+    assert_eq!(*cursors.borrow(), vec![None, Some(2), Some(5), Some(8)]);
+}
+
+#[tokio::test]
+async fn test_empty() {
+    let items: Vec<u32> =
+        paginate(|cursor| async move { Ok::<_, String>(fake_page(cursor, 3, 0)) })
+            .try_collect()
+            .await
+            .unwrap();
+    assert!(items.is_empty());
+}
+
+#[tokio::test]
+async fn test_fetches_pages_lazily() {
+    let fetched = RefCell::new(0);
+    let items = paginate(|cursor| {
+        *fetched.borrow_mut() += 1;
+        async move { Ok::<_, String>(fake_page(cursor, 3, 10)) }
+    });
+    pin_mut!(items);
+
+    let found = items.try_next().await.unwrap();
+    assert_eq!(found, Some(0));
+    assert_eq!(*fetched.borrow(), 1);
+
+    // The item is on the second page, so the later pages are not fetched
+    let found = items.try_filter(|i| future::ready(*i == 4)).next().await;
+    assert_eq!(found, Some(Ok(4)));
+    assert_eq!(*fetched.borrow(), 2);
+}
+
+#[tokio::test]
+async fn test_stops_on_error() {
+    let items: Vec<Result<u32, String>> = paginate(|cursor| async move {
+        match cursor {
+            Some(5) => Err("RPC failed".to_string()),
+            _ => Ok(fake_page(cursor, 3, 10)),
+        }
+    })
+    .collect()
+    .await;
+
+    assert_eq!(items.len(), 7);
+    assert_eq!(items[5], Ok(5));
+    assert_eq!(items[6], Err("RPC failed".to_string()));
+}
+
+#[tokio::test]
+async fn test_starts_after_cursor() {
+    let cursors = RefCell::new(vec![]);
+    let items: Vec<u32> = paginate_from(Some(4), |cursor| {
+        cursors.borrow_mut().push(cursor);
+        async move { Ok::<_, String>(fake_page(cursor, 3, 10)) }
+    })
+    .try_collect()
+    .await
+    .unwrap();
+
+    assert_eq!(items, (5..10).collect::<Vec<_>>());
+    assert_eq!(*cursors.borrow(), vec![Some(4), Some(7)]);
+}
+
+#[tokio::test]
+async fn test_take_page() {
+    let items = |start| {
+        paginate_from(start, |cursor| async move {
+            Ok::<_, String>(fake_page(cursor, 3, 10))
+        })
+    };
+
+    // The page spans several pages of the query
+    let page = take_page(items(None), Some(4), |i| *i).await.unwrap();
+    assert_eq!(page.data, vec![0, 1, 2, 3]);
+    assert_eq!(page.next_cursor, Some(3));
+    assert!(page.has_next_page);
+
+    // The last items exactly fill the page, so there is no next one
+    let page = take_page(items(page.next_cursor), Some(6), |i| *i)
+        .await
+        .unwrap();
+    assert_eq!(page.data, vec![4, 5, 6, 7, 8, 9]);
+    assert!(!page.has_next_page);
+
+    let page = take_page(items(None), None, |i| *i).await.unwrap();
+    assert_eq!(page.data, (0..10).collect::<Vec<_>>());
+    assert!(!page.has_next_page);
+}
+
+#[tokio::test]
+async fn test_take_page_stops_on_error() {
+    let items = paginate(|cursor| async move {
+        match cursor {
+            Some(2) => Err("RPC failed".to_string()),
+            _ => Ok(fake_page(cursor, 3, 10)),
+        }
+    });
+    assert_eq!(
+        take_page(items, Some(5), |i| *i).await.unwrap_err(),
+        "RPC failed"
+    );
+}
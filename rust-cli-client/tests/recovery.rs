@@ -0,0 +1,213 @@
+//! Classifies Sui errors and recovers from them with fake transactions, without a network.
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+use sui_types::base_types::{ObjectID, SuiAddress};
+
+use rust_cli_client::error::{AbortCode, TicTacToeError};
+use rust_cli_client::recovery::{
+    execute_once_with_recovery, execute_with_recovery, Failure, RetryPolicy,
+};
+
+const GAME_ID: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+const GAS_ID: &str = "0x2222222222222222222222222222222222222222222222222222222222222222";
+
+fn id(hex: &str) -> ObjectID {
+    ObjectID::from_hex_literal(hex).unwrap()
+}
+
+fn lock_error(object: &str) -> TicTacToeError {
+    TicTacToeError::from_rejection(&format!(
+        "Failed to execute transaction: QuorumDriverError(ObjectsDoubleUsed {{ conflicting_txes: \
+         {{}} }}) on object ({}, SequenceNumber(3))",
+        object
+    ))
+    .unwrap()
+}
+
+fn stale_error() -> TicTacToeError {
+    TicTacToeError::from_rejection(&format!(
+        "Transaction validator signing failed due to issues with transaction inputs: Object ({}, \
+         SequenceNumber(3)) is not available for consumption, its current version: \
+         SequenceNumber(4)",
+        GAME_ID
+    ))
+    .unwrap()
+}
+
+fn abort_error() -> TicTacToeError {
+    TicTacToeError::MoveAbort {
+        code: AbortCode::ETriedToCheat,
+        function: Some("place_mark".to_string()),
+    }
+}
+
+fn fast_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(2),
+    }
+}
+
+#[test]
+fn test_classify() {
+    assert_eq!(
+        Failure::classify(&lock_error(GAS_ID)),
+        Failure::ObjectLocked(vec![id(GAS_ID)])
+    );
+    assert_eq!(Failure::classify(&stale_error()), Failure::StaleVersion);
+    let gas_error =
+        TicTacToeError::from_rejection("Error checking transaction input objects: InsufficientGas")
+            .unwrap();
+    assert_eq!(Failure::classify(&gas_error), Failure::InsufficientGas);
+    let no_gas_coin = TicTacToeError::NoGasCoin {
+        address: SuiAddress::random_for_testing_only(),
+        budget: 1,
+    };
+    assert_eq!(Failure::classify(&no_gas_coin), Failure::InsufficientGas);
+    assert_eq!(Failure::classify(&abort_error()), Failure::Other);
+}
+
+#[test]
+fn test_not_a_rejection() {
+    // Other errors of the node are left as they are
+    assert!(TicTacToeError::from_rejection("Request timed out").is_none());
+}
+
+#[tokio::test]
+async fn test_retries_stale_version() {
+    let attempts = RefCell::new(0);
+    let res = execute_with_recovery(&fast_policy(), &[id(GAME_ID)], |_| async {
+        *attempts.borrow_mut() += 1;
+        if *attempts.borrow() < 3 {
+            Err(stale_error())
+        } else {
+            Ok(())
+        }
+    })
+    .await;
+    assert!(res.is_ok());
+    assert_eq!(*attempts.borrow(), 3);
+}
+
+#[tokio::test]
+async fn test_excludes_locked_gas() {
+    let excluded = RefCell::new(vec![]);
+    let res = execute_with_recovery(&fast_policy(), &[id(GAME_ID)], |locked_gas| {
+        excluded.borrow_mut().push(locked_gas.clone());
+        async move {
+            if locked_gas.is_empty() {
+                Err(lock_error(GAS_ID))
+            } else {
+                Ok(())
+            }
+        }
+    })
+    .await;
+    assert!(res.is_ok());
+    assert_eq!(*excluded.borrow(), vec![vec![], vec![id(GAS_ID)]]);
+}
+
+#[tokio::test]
+async fn test_locked_game() {
+    let attempts = RefCell::new(0);
+    let res: Result<(), _> = execute_with_recovery(&fast_policy(), &[id(GAME_ID)], |_| async {
+        *attempts.borrow_mut() += 1;
+        Err(lock_error(GAME_ID))
+    })
+    .await;
+
+    // Retrying cannot unlock the game
+    assert_eq!(*attempts.borrow(), 1);
+    let TicTacToeError::GameLocked { objects, .. } = res.unwrap_err() else {
+        panic!("Game should be locked");
+    };
+    assert_eq!(objects, vec![id(GAME_ID)]);
+}
+
+#[tokio::test]
+async fn test_locked_gas_is_not_a_locked_game() {
+    let attempts = RefCell::new(0);
+    let res: Result<(), _> = execute_with_recovery(&fast_policy(), &[id(GAME_ID)], |_| async {
+        *attempts.borrow_mut() += 1;
+        Err(lock_error(GAS_ID))
+    })
+    .await;
+
+    // Every coin tried was locked, but the game can still be played with other coins
+    assert_eq!(*attempts.borrow(), 3);
+    let TicTacToeError::GasLocked(coins) = res.unwrap_err() else {
+        panic!("Gas coins should be locked");
+    };
+    assert_eq!(coins, vec![id(GAS_ID)]);
+}
+
+#[tokio::test]
+async fn test_does_not_repeat_landed_transaction() {
+    let attempts = RefCell::new(0);
+    let checks = RefCell::new(0);
+    let res = execute_once_with_recovery(
+        &fast_policy(),
+        &[],
+        || async {
+            *checks.borrow_mut() += 1;
+            // The first attempt landed, although it failed
+            Ok(Some("landed"))
+        },
+        |_| async {
+            *attempts.borrow_mut() += 1;
+            Err(stale_error())
+        },
+    )
+    .await;
+    assert_eq!(res.unwrap(), "landed");
+    assert_eq!((*attempts.borrow(), *checks.borrow()), (1, 1));
+
+    // Nothing landed, so it is retried
+    let attempts = RefCell::new(0);
+    let res = execute_once_with_recovery(
+        &fast_policy(),
+        &[],
+        || async { Ok(None) },
+        |_| async {
+            *attempts.borrow_mut() += 1;
+            if *attempts.borrow() < 2 {
+                Err(stale_error())
+            } else {
+                Ok("retried")
+            }
+        },
+    )
+    .await;
+    assert_eq!(res.unwrap(), "retried");
+    assert_eq!(*attempts.borrow(), 2);
+}
+
+#[tokio::test]
+async fn test_gives_up() {
+    let attempts = RefCell::new(0);
+    let res: Result<(), _> = execute_with_recovery(&fast_policy(), &[], |_| async {
+        *attempts.borrow_mut() += 1;
+        Err(stale_error())
+    })
+    .await;
+    assert_eq!(*attempts.borrow(), 3);
+    assert!(matches!(
+        res.unwrap_err(),
+        TicTacToeError::RetriesExhausted { attempts: 3, .. }
+    ));
+}
+
+#[tokio::test]
+async fn test_no_retry_on_other_failures() {
+    let attempts = RefCell::new(0);
+    let res: Result<(), _> = execute_with_recovery(&fast_policy(), &[], |_| async {
+        *attempts.borrow_mut() += 1;
+        Err(abort_error())
+    })
+    .await;
+    assert!(res.is_err());
+    assert_eq!(*attempts.borrow(), 1);
+}
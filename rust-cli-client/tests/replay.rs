@@ -0,0 +1,87 @@
+//! Steps through the moves of a finished game.
+
+use sui_types::base_types::{SequenceNumber, SuiAddress, TransactionDigest};
+
+use rust_cli_client::game_engine::Board;
+use rust_cli_client::history::{rebuild_history, MarkCall, MarkTransaction};
+use rust_cli_client::replay::{Replay, ReplayControl};
+use rust_cli_client::startup::Player;
+
+/// X wins on the first column, after O tried a taken cell
+fn finished_replay() -> Replay {
+    let moves = [(0, 0), (0, 0), (0, 1), (1, 0), (1, 1), (2, 0)];
+    let sender = SuiAddress::random_for_testing_only();
+    let txs = moves
+        .iter()
+        .flat_map(|&(row, col)| [MarkCall::Send { row, col }, MarkCall::Place])
+        .enumerate()
+        .map(|(version, call)| MarkTransaction {
+            digest: TransactionDigest::random(),
+            sender,
+            timestamp_ms: None,
+            mark_version: SequenceNumber::from_u64(version as u64 + 1),
+            call,
+        })
+        .collect();
+    Replay::new(rebuild_history(txs).unwrap())
+}
+
+#[test]
+fn test_parse_controls() {
+    assert_eq!("".parse::<ReplayControl>().unwrap(), ReplayControl::Next);
+    assert_eq!("b\n".parse::<ReplayControl>().unwrap(), ReplayControl::Back);
+    assert_eq!(
+        "4".parse::<ReplayControl>().unwrap(),
+        ReplayControl::Jump(4)
+    );
+    assert_eq!("l".parse::<ReplayControl>().unwrap(), ReplayControl::Last);
+    assert_eq!(
+        "quit".parse::<ReplayControl>().unwrap(),
+        ReplayControl::Quit
+    );
+    assert!("-1".parse::<ReplayControl>().is_err());
+    assert!("x".parse::<ReplayControl>().is_err());
+}
+
+#[test]
+fn test_step_through() {
+    let mut replay = finished_replay();
+    assert_eq!(replay.len(), 6);
+    assert_eq!(replay.position(), 0);
+    assert!(replay.current().is_none());
+    assert_eq!(replay.board(), Board::new());
+
+    // Cannot go before the first move
+    assert!(replay.apply(ReplayControl::Back));
+    assert_eq!(replay.position(), 0);
+
+    replay.apply(ReplayControl::Next);
+    replay.apply(ReplayControl::Next);
+    let rejected = replay.current().unwrap();
+    assert!(rejected.is_rejected());
+    assert_eq!(rejected.player, Player::O);
+
+    replay.apply(ReplayControl::Back);
+    assert_eq!(replay.current().unwrap().player, Player::X);
+}
+
+#[test]
+fn test_jump_and_winning_line() {
+    let mut replay = finished_replay();
+    replay.apply(ReplayControl::Jump(4));
+    assert_eq!(replay.position(), 4);
+    assert_eq!(replay.board().winning_line(), None);
+
+    // Jumps past the last move stop at it
+    replay.apply(ReplayControl::Jump(42));
+    assert_eq!(replay.position(), 6);
+    assert_eq!(replay.board().winning_line(), Some([0, 1, 2]));
+    replay.apply(ReplayControl::Next);
+    assert_eq!(replay.position(), 6);
+
+    replay.apply(ReplayControl::First);
+    assert_eq!(replay.position(), 0);
+    replay.apply(ReplayControl::Last);
+    assert_eq!(replay.position(), 6);
+    assert!(!replay.apply(ReplayControl::Quit));
+}
@@ -0,0 +1,198 @@
+//! Drives the turn state machine with observed games, without a terminal or a network.
+
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::id::{ID, UID};
+
+use rust_cli_client::contract_structs::TicTacToe;
+use rust_cli_client::startup::Player;
+use rust_cli_client::turn_state_machine::{Outcome, TurnAction, TurnState, TurnStateMachine};
+
+struct Addresses {
+    x: SuiAddress,
+    o: SuiAddress,
+    multisig: SuiAddress,
+}
+
+fn addresses() -> Addresses {
+    Addresses {
+        x: SuiAddress::random_for_testing_only(),
+        o: SuiAddress::random_for_testing_only(),
+        multisig: SuiAddress::random_for_testing_only(),
+    }
+}
+
+fn game(addrs: &Addresses, cur_turn: u8, finished: u8) -> TicTacToe {
+    TicTacToe {
+        id: UID {
+            id: ID {
+                bytes: ObjectID::random(),
+            },
+        },
+        gameboard: vec![0; 9],
+        cur_turn,
+        x_addr: addrs.x,
+        o_addr: addrs.o,
+        finished,
+    }
+}
+
+#[test]
+fn test_starts_by_observing() {
+    let addrs = addresses();
+    let machine = TurnStateMachine::new(Player::X, addrs.x, addrs.multisig);
+    assert_eq!(machine.state(), TurnState::Recovering);
+    assert_eq!(machine.next_action(), TurnAction::Observe);
+}
+
+#[test]
+fn test_full_turn() {
+    let addrs = addresses();
+    let mut machine = TurnStateMachine::new(Player::X, addrs.x, addrs.multisig);
+
+    assert_eq!(
+        machine.observe(&game(&addrs, 0, 0), Some(addrs.x)).unwrap(),
+        TurnState::MyTurnNeedInput
+    );
+    assert_eq!(machine.next_action(), TurnAction::SendMark);
+
+    assert_eq!(machine.mark_sent(), TurnState::MarkSentAwaitingPlace);
+    assert_eq!(machine.next_action(), TurnAction::PlaceMark);
+
+    assert_eq!(machine.place_submitted(), TurnState::PlaceSubmitted);
+    assert_eq!(machine.next_action(), TurnAction::WaitForUpdate);
+
+    // Mark placed, it is O's turn
+    assert_eq!(
+        machine.observe(&game(&addrs, 1, 0), Some(addrs.o)).unwrap(),
+        TurnState::AwaitingOpponent
+    );
+    assert_eq!(machine.next_action(), TurnAction::WaitForUpdate);
+
+    // O sent the mark, but has not placed it yet
+    assert_eq!(
+        machine
+            .observe(&game(&addrs, 1, 0), Some(addrs.multisig))
+            .unwrap(),
+        TurnState::AwaitingOpponent
+    );
+
+    assert_eq!(
+        machine.observe(&game(&addrs, 2, 0), Some(addrs.x)).unwrap(),
+        TurnState::MyTurnNeedInput
+    );
+}
+
+#[test]
+fn test_rejected_move() {
+    let addrs = addresses();
+    let mut machine = TurnStateMachine::new(Player::O, addrs.o, addrs.multisig);
+    machine.observe(&game(&addrs, 1, 0), Some(addrs.o)).unwrap();
+    machine.mark_sent();
+    machine.place_submitted();
+
+    // The cell was taken, so the mark came back during the same turn
+    assert_eq!(
+        machine.observe(&game(&addrs, 1, 0), Some(addrs.o)).unwrap(),
+        TurnState::MyTurnNeedInput
+    );
+}
+
+#[test]
+fn test_move_rejected() {
+    let addrs = addresses();
+    let mut machine = TurnStateMachine::new(Player::O, addrs.o, addrs.multisig);
+    machine.observe(&game(&addrs, 1, 0), Some(addrs.o)).unwrap();
+
+    // The whole move was made at once and rejected, so the board is fetched again
+    assert_eq!(machine.move_rejected(), TurnState::Recovering);
+    assert_eq!(machine.next_action(), TurnAction::Observe);
+    assert_eq!(
+        machine.observe(&game(&addrs, 1, 0), Some(addrs.o)).unwrap(),
+        TurnState::MyTurnNeedInput
+    );
+}
+
+#[test]
+fn test_place_not_yet_observed() {
+    let addrs = addresses();
+    let mut machine = TurnStateMachine::new(Player::X, addrs.x, addrs.multisig);
+    machine.observe(&game(&addrs, 0, 0), Some(addrs.x)).unwrap();
+    machine.mark_sent();
+    machine.place_submitted();
+
+    // A lagging node still shows the mark as sent, which should not be placed again
+    assert_eq!(
+        machine
+            .observe(&game(&addrs, 0, 0), Some(addrs.multisig))
+            .unwrap(),
+        TurnState::PlaceSubmitted
+    );
+    assert_eq!(machine.next_action(), TurnAction::WaitForUpdate);
+}
+
+#[test]
+fn test_resumes_after_sent_mark() {
+    let addrs = addresses();
+    let mut machine = TurnStateMachine::new(Player::X, addrs.x, addrs.multisig);
+
+    // Mark was sent in a previous session, but not placed
+    assert_eq!(
+        machine
+            .observe(&game(&addrs, 2, 0), Some(addrs.multisig))
+            .unwrap(),
+        TurnState::MarkSentAwaitingPlace
+    );
+    assert_eq!(machine.next_action(), TurnAction::PlaceMark);
+}
+
+#[test]
+fn test_recovering() {
+    let addrs = addresses();
+    let mut machine = TurnStateMachine::new(Player::X, addrs.x, addrs.multisig);
+    machine.observe(&game(&addrs, 0, 0), Some(addrs.x)).unwrap();
+    machine.mark_sent();
+
+    assert_eq!(machine.transaction_failed(), TurnState::Recovering);
+    assert_eq!(machine.next_action(), TurnAction::Observe);
+
+    // Inconsistent observation, so wait for the next change instead of observing again
+    assert_eq!(
+        machine.observe(&game(&addrs, 0, 0), None).unwrap(),
+        TurnState::Recovering
+    );
+    assert_eq!(machine.next_action(), TurnAction::WaitForUpdate);
+
+    assert_eq!(
+        machine
+            .observe(&game(&addrs, 0, 0), Some(addrs.multisig))
+            .unwrap(),
+        TurnState::MarkSentAwaitingPlace
+    );
+}
+
+#[test]
+fn test_finished() {
+    let addrs = addresses();
+    for (finished, outcome) in [
+        (1, Outcome::Won(Player::X)),
+        (2, Outcome::Won(Player::O)),
+        (3, Outcome::Draw),
+    ] {
+        let mut machine = TurnStateMachine::new(Player::O, addrs.o, addrs.multisig);
+        assert_eq!(
+            machine.observe(&game(&addrs, 5, finished), None).unwrap(),
+            TurnState::Finished(outcome)
+        );
+        assert_eq!(machine.next_action(), TurnAction::Done);
+        // Nothing can change a finished game
+        assert_eq!(machine.transaction_failed(), TurnState::Finished(outcome));
+    }
+}
+
+#[test]
+fn test_invalid_game_state() {
+    let addrs = addresses();
+    let mut machine = TurnStateMachine::new(Player::X, addrs.x, addrs.multisig);
+    // Retrying cannot make sense of it
+    assert!(machine.observe(&game(&addrs, 0, 4), Some(addrs.x)).is_err());
+}
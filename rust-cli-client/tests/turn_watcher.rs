@@ -0,0 +1,117 @@
+//! Watches games on the in-memory backend, which pushes its changes to the watcher. The Sui client
+//! subscribes to the websocket of a fullnode, so neither its subscription nor the polling it falls
+//! back on are covered here.
+
+use std::time::Duration;
+
+use futures::{pin_mut, StreamExt};
+use sui_types::crypto::SignatureScheme;
+use tokio::time::timeout;
+
+use rust_cli_client::game_backend::GameBackend;
+use rust_cli_client::in_memory_backend::InMemoryBackend;
+use rust_cli_client::multi_sig_1_out_of_n::MultiSig1OutOfN;
+use rust_cli_client::row_col::RowCol;
+use rust_cli_client::startup::Player;
+use rust_cli_client::turn_watcher::{watch_turns, GameUpdate, PollConfig};
+
+mod common;
+use common::new_key_pair;
+
+/// Way longer than the tests wait, so updates can only come from pushed changes
+fn never_poll() -> PollConfig {
+    PollConfig {
+        initial_interval: Duration::from_secs(3600),
+        max_interval: Duration::from_secs(3600),
+        multiplier: 2,
+    }
+}
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[tokio::test]
+async fn test_updates_on_moves() {
+    let backend = InMemoryBackend::new();
+    let x_signer = new_key_pair(SignatureScheme::ED25519);
+    let o_signer = new_key_pair(SignatureScheme::ED25519);
+    let multisig = MultiSig1OutOfN::new(vec![x_signer.public(), o_signer.public()]).unwrap();
+    let game_info = backend.create_game(&x_signer, &multisig).await.unwrap();
+
+    let x_updates = watch_turns(&backend, game_info.clone(), Player::X, never_poll());
+    let o_updates = watch_turns(&backend, game_info.clone(), Player::O, never_poll());
+    pin_mut!(x_updates, o_updates);
+
+    // Current state first
+    let update = x_updates.next().await.unwrap().unwrap();
+    assert!(matches!(update, GameUpdate::MyTurn(_)));
+    let update = o_updates.next().await.unwrap().unwrap();
+    assert!(matches!(update, GameUpdate::OpponentsTurn(_)));
+
+    let moves = [
+        (&x_signer, 0, 0),
+        (&o_signer, 0, 1),
+        (&x_signer, 1, 1),
+        (&o_signer, 0, 2),
+        (&x_signer, 2, 2),
+    ];
+    for (i, (signer, row, col)) in moves.into_iter().enumerate() {
+        backend
+            .send_mark_to_game(
+                signer,
+                game_info.mark_id,
+                RowCol::try_from(row).unwrap(),
+                RowCol::try_from(col).unwrap(),
+            )
+            .await
+            .unwrap();
+        backend
+            .place_mark(signer, &multisig, game_info.game_id, game_info.mark_id)
+            .await
+            .unwrap();
+
+        let x_update = timeout(TIMEOUT, x_updates.next()).await.unwrap();
+        let o_update = timeout(TIMEOUT, o_updates.next()).await.unwrap();
+        let (x_update, o_update) = (x_update.unwrap().unwrap(), o_update.unwrap().unwrap());
+        if i == moves.len() - 1 {
+            // The winning move does not start another turn
+            for update in [&x_update, &o_update] {
+                assert!(matches!(update, GameUpdate::Finished(_)));
+                assert_eq!(update.game().cur_turn, 4);
+                assert_eq!(update.game().finished, 1);
+            }
+            continue;
+        }
+        assert_eq!(x_update.game().cur_turn as usize, i + 1);
+        assert_eq!(o_update.game().cur_turn as usize, i + 1);
+        if i % 2 == 1 {
+            assert!(matches!(x_update, GameUpdate::MyTurn(_)));
+            assert!(matches!(o_update, GameUpdate::OpponentsTurn(_)));
+        } else {
+            assert!(matches!(x_update, GameUpdate::OpponentsTurn(_)));
+            assert!(matches!(o_update, GameUpdate::MyTurn(_)));
+        }
+    }
+
+    // Streams end with the game
+    assert!(x_updates.next().await.is_none());
+    assert!(o_updates.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_no_update_without_changes() {
+    let backend = InMemoryBackend::new();
+    let x_signer = new_key_pair(SignatureScheme::ED25519);
+    let o_signer = new_key_pair(SignatureScheme::ED25519);
+    let multisig = MultiSig1OutOfN::new(vec![x_signer.public(), o_signer.public()]).unwrap();
+    let game_info = backend.create_game(&x_signer, &multisig).await.unwrap();
+
+    let updates = watch_turns(&backend, game_info, Player::O, never_poll());
+    pin_mut!(updates);
+    updates.next().await.unwrap().unwrap();
+
+    // Transactions on other games do not wake the watcher up with an update
+    backend.create_game(&o_signer, &multisig).await.unwrap();
+    assert!(timeout(Duration::from_millis(100), updates.next())
+        .await
+        .is_err());
+}